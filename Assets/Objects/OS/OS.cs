@@ -6,11 +6,35 @@ public class OS : Terminal
     [SerializeField]
     IPC.InitializeOSMessage initializeOSMessage;
 
+    /// <summary>
+    /// The terminal's game state, sent back to it on connect.
+    /// Save slots can persist and restore this.
+    /// </summary>
+    public static byte[] SavedState { get; set; }
+
+    /// <summary>
+    /// The game time to start the terminal's clock at, overriding the saved one.
+    /// Only sent on connect, since the terminal reads no messages afterwards.
+    /// </summary>
+    public static IPC.SetClockMessage? Clock { get; set; }
+
     protected override void OnConnected()
     {
         base.OnConnected();
 
         Debug.Log($"Connected to OS. ComputerID: {initializeOSMessage.ComputerID}");
+        if (SavedState != null)
+        {
+            TerminalMode.Instance.Server.WriteStateMessage(new IPC.StateMessage
+            {
+                Type = IPC.MessageType.LoadState,
+                Data = SavedState,
+            });
+        }
+        if (Clock is IPC.SetClockMessage clock)
+        {
+            TerminalMode.Instance.Server.WriteMessage(clock);
+        }
         TerminalMode.Instance.Server.WriteMessage(initializeOSMessage);
     }
 
@@ -18,7 +42,11 @@ public class OS : Terminal
     {
         base.OnMessage(message);
 
-        if (message is IPC.SwitchComputersMessage switchComputersMessage)
+        if (message is IPC.StateMessage { Type: IPC.MessageType.SaveState } stateMessage)
+        {
+            SavedState = stateMessage.Data;
+        }
+        else if (message is IPC.SwitchComputersMessage switchComputersMessage)
         {
 			RoomManager.Instance.LoadRoomAsync(switchComputersMessage.NewID).ContinueWith((task) => {
 				Debug.Log(Room.Instance);
@@ -12,6 +12,9 @@ namespace IPC
         UnlockDoor = 1,
         SwitchComputers = 2,
         PlaySfx = 3,
+        SaveState = 5,
+        LoadState = 6,
+        SetClock = 7,
     }
 
     public enum ComputerID : uint
@@ -85,4 +88,31 @@ namespace IPC
 
         public uint ID;
     }
+
+    /// <summary>
+    /// Sets the terminal's game clock. Only read before InitializeOS.
+    /// </summary>
+    [Serializable]
+    [StructLayout(LayoutKind.Sequential)]
+    public struct SetClockMessage : IMessage
+    {
+        public MessageType Type => MessageType.SetClock;
+
+        /// <summary>Game time, in seconds since the Unix epoch.</summary>
+        public long Timestamp;
+        /// <summary>Game seconds per real second. 0 freezes the clock.</summary>
+        public double Scale;
+    }
+
+    /// <summary>
+    /// Opaque game state. Unlike other messages, the body is just the raw bytes,
+    /// so the header length is the length of Data.
+    /// </summary>
+    [Serializable]
+    public struct StateMessage : IMessage
+    {
+        public MessageType Type { get; set; }
+
+        public byte[] Data;
+    }
 }
\ No newline at end of file
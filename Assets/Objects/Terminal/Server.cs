@@ -112,6 +112,17 @@ namespace IPC
             Write(message);
         }
 
+        public void WriteStateMessage(StateMessage message)
+        {
+            Write(new MessageHeader
+            {
+                Type = message.Type,
+                Length = (uint)message.Data.Length,
+            });
+
+            Write(message.Data);
+        }
+
         public Task WriteMessageAsync<T>(T message)
             where T : struct, IMessage
         {
@@ -195,6 +206,12 @@ namespace IPC
                     return await Internal<PlaySfxMessage>();
                 case MessageType.SwitchComputers:
                     return await Internal<SwitchComputersMessage>();
+                case MessageType.SaveState:
+                    return new StateMessage
+                    {
+                        Type = MessageType.SaveState,
+                        Data = await ReadExactlyAsync((int)messageHeader.Length, cancellationToken),
+                    };
                 default:
                     UnityEngine.Debug.LogError($"Received unknown message type: {messageHeader.Type}");
                     throw new NotImplementedException($"Unknown message sent: {messageHeader.Type}");
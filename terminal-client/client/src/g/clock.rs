@@ -0,0 +1,77 @@
+//! The in-game clock, which dates files, schedules cron jobs and is shown by
+//! `date`. It runs from a time set by the world, or synced from Unity, at a
+//! scale that can speed it up or freeze it.
+
+use std::{cell::Cell, time::Instant};
+
+use chrono::Duration;
+
+use super::fs::NodeDateTime;
+
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    /// The game time when `real` was taken.
+    time: NodeDateTime,
+    real: Instant,
+    /// Game seconds per real second.
+    scale: f64,
+}
+
+#[derive(Debug)]
+pub struct Clock(Cell<Anchor>);
+
+impl Default for Clock {
+    /// A clock running at real time from the real current time.
+    fn default() -> Self {
+        Self::new(chrono::Utc::now())
+    }
+}
+
+impl Clock {
+    pub fn new(time: NodeDateTime) -> Self {
+        Self(Cell::new(Anchor {
+            time,
+            real: Instant::now(),
+            scale: 1.0,
+        }))
+    }
+
+    pub fn now(&self) -> NodeDateTime {
+        let anchor = self.0.get();
+        let elapsed = anchor.real.elapsed().as_secs_f64() * anchor.scale;
+        anchor.time + Duration::milliseconds((elapsed * 1000.0) as i64)
+    }
+
+    /// Jumps to `time`, keeping the scale.
+    pub fn set(&self, time: NodeDateTime) {
+        self.0.set(Anchor {
+            time,
+            real: Instant::now(),
+            scale: self.scale(),
+        });
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.0.get().scale
+    }
+
+    /// Makes the clock run `scale` times as fast as real time from now on.
+    /// A scale of 0 freezes it.
+    pub fn set_scale(&self, scale: f64) {
+        self.0.set(Anchor {
+            time: self.now(),
+            real: Instant::now(),
+            scale: scale.max(0.0),
+        });
+    }
+}
+
+/// Formats a file's date as `ls` does: with the time if it is within six
+/// months of `now`, and with the year otherwise.
+pub fn format_file_date(date: &NodeDateTime, now: &NodeDateTime) -> String {
+    if (*now - *date).num_days().abs() < 183 {
+        date.format("%b %e %H:%M").to_string()
+    } else {
+        date.format("%b %e  %Y").to_string()
+    }
+}
@@ -1,10 +1,19 @@
 use bevy_reflect::Reflect;
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-
-use crate::path;
-
-use super::fs::{Dir, File, Node, NodeContent, NodeDateTime, Path, Root};
+use std::rc::Rc;
+
+use crate::{ipc, path};
+
+use super::clock::Clock;
+use super::cron::{crontab_path, CronJob};
+use super::fs::{
+    home_dir, Dir, File, FsError, FsResult, Node, NodeContent, NodeDateTime, Path, Root, Security,
+    SecurityMode,
+};
+use super::glob;
+use super::hash::{HashKind, PasswordHash};
+use super::process::ProcessTable;
 use super::subprocess::SubprocessFn;
 
 #[repr(u32)]
@@ -16,19 +25,63 @@ pub enum ComputerId {
 
 type ComputerAddress = String;
 
+/// The file in each user's home that `cmd` sources when a session starts.
+pub const RC_FILE: &str = ".cmdrc";
+/// The file in each user's home that `cmd` keeps their command history in.
+pub const HISTORY_FILE: &str = ".history";
+
 #[derive(Clone)]
 pub struct User {
     pub name: String,
-    pub password: String,
+    pub password: PasswordHash,
+    /// Supplementary groups. Every user is implicitly in the group of their own name.
+    pub groups: Vec<String>,
 }
 
 impl User {
-    pub fn new<T: Into<String>, U: Into<String>>(name: T, password: U) -> Self {
+    /// Creates a user whose password is stored as a strong hash.
+    pub fn new<T: Into<String>, U: AsRef<str>>(name: T, password: U) -> Self {
+        Self::with_hash_kind(name, password, HashKind::Strong)
+    }
+
+    /// Creates a user whose password is stored as a weak, quickly cracked hash.
+    pub fn new_weak<T: Into<String>, U: AsRef<str>>(name: T, password: U) -> Self {
+        Self::with_hash_kind(name, password, HashKind::Weak)
+    }
+
+    fn with_hash_kind<T: Into<String>, U: AsRef<str>>(
+        name: T,
+        password: U,
+        kind: HashKind,
+    ) -> Self {
+        let name = name.into();
         Self {
-            name: name.into(),
-            password: password.into(),
+            password: PasswordHash::new(kind, password.as_ref(), &PasswordHash::salt_from(&name)),
+            name,
+            groups: Vec::new(),
         }
     }
+
+    pub fn check_password(&self, password: &str) -> bool {
+        self.password.verify(password)
+    }
+
+    pub fn with_groups<T: ToString, It: IntoIterator<Item = T>>(mut self, groups: It) -> Self {
+        self.groups = groups.into_iter().map(|g| g.to_string()).collect();
+        self
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.name == "root"
+    }
+
+    pub fn in_group(&self, group: &str) -> bool {
+        self.name == group || self.groups.iter().any(|g| g == group)
+    }
+
+    pub fn home(&self) -> Path {
+        home_dir(&self.name)
+    }
 }
 
 #[derive(Clone)]
@@ -41,13 +94,27 @@ pub struct Computer {
     pub id: ComputerId,
     pub address: ComputerAddress,
 
+    /// Users, or groups prefixed with `%`, that may use `sudo`.
+    pub sudoers: Vec<String>,
+
     pub cwd: RefCell<Path>,
     pub root: Root,
     pub env: RefCell<HashMap<String, String>>,
+    pub processes: RefCell<ProcessTable>,
+    /// Total memory, in KiB.
+    pub memory: u64,
+    /// Jobs of the world, run besides those in the users' crontabs.
+    pub cron: Vec<CronJob>,
+    /// The game's clock, see `Game::clock`.
+    pub clock: Rc<Clock>,
 }
 
 impl Default for Computer {
     fn default() -> Self {
+        let root = Root::new([]);
+        let mut processes = ProcessTable::default();
+        processes.spawn("root", "init", root.node.borrow().date, true);
+
         Self {
             should_quit: Cell::new(false),
 
@@ -57,13 +124,19 @@ impl Default for Computer {
             id: ComputerId::First,
             address: Default::default(),
 
+            sudoers: Default::default(),
+
             cwd: Default::default(),
-            root: Root::new([]),
+            root,
 
             env: RefCell::new(HashMap::from([(
                 "PS1".to_string(),
                 "\\u@\\H \\w$ ".to_string(),
             )])),
+            processes: RefCell::new(processes),
+            memory: 2 * 1024 * 1024,
+            cron: Vec::new(),
+            clock: Default::default(),
         }
     }
 }
@@ -71,24 +144,67 @@ impl Default for Computer {
 #[allow(dead_code)]
 impl Computer {
     pub fn path(&self) -> String {
-        self.env
-            .borrow()
-            .get(&"path".to_string())
+        let env = self.env.borrow();
+        // older saves stored the search path under a lowercase key
+        env.get("PATH")
+            .or_else(|| env.get("path"))
             .cloned()
             .unwrap_or_default()
     }
 
+    /// Splits `PATH` on either the unix `:` or the windows `;` separator.
     pub fn parsed_path(&self) -> Vec<String> {
-        self.path().split(';').map(|s| s.to_string()).collect()
+        self.path()
+            .split([':', ';'])
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    /// Whether the current user may run `node`. Executables need execute
+    /// permission. Files are scripts if they start with a shebang or have
+    /// execute permission, and also need read permission.
+    pub fn can_run(&self, node: &Node) -> bool {
+        let user = self.current_user();
+        let data = node.borrow();
+        match &data.content {
+            NodeContent::Executable(_) => data.permits(user, SecurityMode::Execute),
+            NodeContent::File(f) => {
+                let executable = if user.is_root() {
+                    [
+                        data.owner_security,
+                        data.group_security,
+                        data.other_security,
+                    ]
+                    .iter()
+                    .any(|security| security.contains(SecurityMode::Execute))
+                } else {
+                    data.security_for(user).contains(SecurityMode::Execute)
+                };
+                data.permits(user, SecurityMode::Read)
+                    && (f.content.starts_with("#!") || executable)
+            }
+            NodeContent::Dir(_) => false,
+        }
     }
 
+    /// Finds `exe` in the path, or at `exe` itself if it contains a `/`.
     pub fn which(&self, exe: &str) -> Option<(Path, Node)> {
+        if exe.contains('/') {
+            let exe_path = self.parse_path(exe);
+            return self
+                .lookup(&exe_path)
+                .ok()
+                .filter(|node| self.can_run(node))
+                .map(|node| (exe_path, node));
+        }
+
         let path = self.parsed_path();
         path.iter().find_map(|entry| {
-            let exe_path = Path::parse(&Path::default(), entry).join(&path![exe]);
-            self.root
-                .get_node(&exe_path)
-                .and_then(|node| if node.is_exe() { Some(node) } else { None })
+            let exe_path = Path::parse(&Path::default(), &self.home(), entry).join(&path![exe]);
+            self.lookup(&exe_path)
+                .ok()
+                .filter(|node| self.can_run(node))
                 .map(|node| (exe_path, node))
         })
     }
@@ -105,16 +221,481 @@ impl Computer {
         return self.users.iter().find(|user| user.name == name);
     }
 
+    pub fn find_user_index(&self, name: &str) -> Option<usize> {
+        self.users.iter().position(|user| user.name == name)
+    }
+
+    pub fn group_exists(&self, group: &str) -> bool {
+        self.users.iter().any(|user| user.in_group(group))
+    }
+
     pub fn current_user(&self) -> &User {
         self.users
             .get(self.current_user_index.get())
             .expect("User index out of range")
     }
 
+    pub fn home(&self) -> Path {
+        self.current_user().home()
+    }
+
+    pub fn uid(&self, user: &User) -> usize {
+        if user.is_root() {
+            0
+        } else {
+            1000 + self
+                .users
+                .iter()
+                .filter(|u| !u.is_root())
+                .position(|u| u.name == user.name)
+                .unwrap_or_default()
+        }
+    }
+
+    pub fn can_sudo(&self, user: &User) -> bool {
+        user.is_root()
+            || self
+                .sudoers
+                .iter()
+                .any(|entry| match entry.strip_prefix('%') {
+                    Some(group) => user.in_group(group),
+                    None => *entry == user.name,
+                })
+    }
+
+    /// Regenerates `/etc/passwd`, `/etc/shadow` and `/etc/sudoers` from `users` and `sudoers`.
+    pub fn write_user_files(&self) {
+        let etc = path!["etc"];
+        let date = self.root.node.borrow().date;
+        if self.root.get_node(&etc).is_none() {
+            let _ = self
+                .root
+                .node
+                .add_child(Node::dir("etc", date, Dir::empty()));
+        }
+
+        let passwd = self
+            .users
+            .iter()
+            .map(|user| {
+                let uid = self.uid(user);
+                format!("{}:x:{uid}:{uid}::{}:/bin/cmd\n", user.name, user.home())
+            })
+            .collect::<String>();
+        let shadow = self
+            .users
+            .iter()
+            .map(|user| format!("{}:{}:::\n", user.name, user.password))
+            .collect::<String>();
+        let sudoers = self
+            .sudoers
+            .iter()
+            .map(|entry| format!("{entry} ALL=(ALL) ALL\n"))
+            .collect::<String>();
+
+        let none = Security::none();
+        let files = [
+            (
+                "passwd",
+                passwd,
+                SecurityMode::RW.into(),
+                SecurityMode::Read.into(),
+            ),
+            ("shadow", shadow, SecurityMode::RW.into(), none),
+            ("sudoers", sudoers, SecurityMode::Read.into(), none),
+        ];
+        for (name, content, owner, others) in files {
+            match self.root.get_node(&etc.clone().join(&path![name])) {
+                Some(node) => {
+                    if let NodeContent::File(file) = &mut node.borrow_mut().content {
+                        file.content = content;
+                    }
+                }
+                None => {
+                    let node = Node::file(name, date, File::new(content))
+                        .with_security::<Security, Security, Security>(owner, others, others);
+                    let _ = self.root.node.add_node(&etc, node);
+                }
+            }
+        }
+    }
+
+    /// Resolves a path typed by the current user against the cwd and their home.
+    pub fn parse_path(&self, relative: &str) -> Path {
+        Path::parse(&self.cwd.borrow(), &self.home(), relative)
+    }
+
+    pub fn check_access(&self, node: &Node, mode: SecurityMode) -> FsResult {
+        if node.borrow().permits(self.current_user(), mode) {
+            Ok(())
+        } else {
+            Err(FsError::PermissionDenied)
+        }
+    }
+
+    /// Like `Root::get_node`, but requires the current user to be able to
+    /// traverse (execute) every directory on the way.
+    pub fn lookup(&self, path: &Path) -> FsResult<Node> {
+        let mut node = self.root.node.clone();
+        for name in &path.0 {
+            let dir = node.as_dir().ok_or(FsError::NotDirectory)?;
+            self.check_access(&node, SecurityMode::Execute)?;
+            node = dir.get_child(name).ok_or(FsError::DoesNotExist)?;
+        }
+        Ok(node)
+    }
+
+    /// Reads the file at `path` as the current user.
+    pub fn read_file(&self, path: &Path) -> FsResult<String> {
+        let node = self.lookup(path)?;
+        self.check_access(&node, SecurityMode::Read)?;
+        let content = match &node.borrow().content {
+            NodeContent::File(f) => Ok(f.content.clone()),
+            NodeContent::Dir(_) => Err(FsError::IsDirectory),
+            NodeContent::Executable(_) => Err(FsError::NotFile),
+        };
+        content
+    }
+
+    /// Writes `content` to the file at `path` as the current user, creating it
+    /// if it does not exist. With `append`, adds to the end of the file instead.
+    pub fn write_file(&self, path: &Path, content: &str, append: bool) -> FsResult {
+        let node = match self.lookup(path) {
+            Ok(node) => node,
+            Err(FsError::DoesNotExist) => {
+                let parent = self.writable_parent(path)?;
+                let user = self.current_user();
+                let name = path.basename().ok_or(FsError::IsDirectory)?;
+                let now = self.clock.now();
+                let node =
+                    Node::file(name, now, File::new(content)).owned_by(&user.name, &user.name);
+                parent.add_child(node)?;
+                parent.borrow_mut().date = now;
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.check_access(&node, SecurityMode::Write)?;
+        let mut data = node.borrow_mut();
+        match &mut data.content {
+            NodeContent::File(f) if append => f.content.push_str(content),
+            NodeContent::File(f) => f.content = content.to_string(),
+            NodeContent::Dir(_) => return Err(FsError::IsDirectory),
+            NodeContent::Executable(_) => return Err(FsError::NotFile),
+        }
+        data.date = self.clock.now();
+        Ok(())
+    }
+
+    /// Looks up the directory containing `path`, checking that the current
+    /// user may add and remove entries in it.
+    fn writable_parent(&self, path: &Path) -> FsResult<Node> {
+        let parent = self.lookup(&path.parent())?;
+        if !parent.is_dir() {
+            return Err(FsError::NotDirectory);
+        }
+        self.check_access(&parent, SecurityMode::Write)?;
+        self.check_access(&parent, SecurityMode::Execute)?;
+        Ok(parent)
+    }
+
+    /// Where `src` ends up when moved or copied to `dest`: inside `dest` if it
+    /// is a directory, and at `dest` itself otherwise.
+    pub fn target_path(&self, src: &Path, dest: &Path) -> Path {
+        match (self.lookup(dest), src.basename()) {
+            (Ok(node), Some(name)) if node.is_dir() => dest.clone().join(&Path::new(vec![name])),
+            _ => dest.clone(),
+        }
+    }
+
+    /// Creates an empty directory at `path`, owned by the current user.
+    pub fn create_dir(&self, path: &Path) -> FsResult {
+        match self.lookup(path) {
+            Ok(_) => return Err(FsError::AlreadyExists),
+            Err(FsError::DoesNotExist) => {}
+            Err(e) => return Err(e),
+        }
+        let parent = self.writable_parent(path)?;
+        let user = self.current_user();
+        let name = path.basename().ok_or(FsError::AlreadyExists)?;
+        let now = self.clock.now();
+        parent.add_child(Node::dir(name, now, Dir::empty()).owned_by(&user.name, &user.name))?;
+        parent.borrow_mut().date = now;
+        Ok(())
+    }
+
+    /// Updates the date of the node at `path`, or creates an empty file there.
+    pub fn touch(&self, path: &Path) -> FsResult {
+        match self.lookup(path) {
+            Ok(node) => {
+                self.check_access(&node, SecurityMode::Write)?;
+                node.borrow_mut().date = self.clock.now();
+                Ok(())
+            }
+            Err(FsError::DoesNotExist) => self.write_file(path, "", false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Removes the node at `path`. Directories are only removed if
+    /// `recursive`, and only if the current user may empty every directory
+    /// inside them. The current directory and those above it, as `.` and
+    /// `..` name them, cannot be removed.
+    pub fn remove(&self, path: &Path, recursive: bool) -> FsResult {
+        let node = self.lookup(path)?;
+        if self.cwd.borrow().0.starts_with(&path.0) {
+            return Err(FsError::InvalidArgument);
+        }
+        let name = path.basename().ok_or(FsError::PermissionDenied)?;
+        let parent = self.writable_parent(path)?;
+        if node.is_dir() {
+            if !recursive {
+                return Err(FsError::IsDirectory);
+            }
+            let user = self.current_user();
+            let mut allowed = true;
+            node.walk(&mut |n| {
+                let data = n.borrow();
+                if let NodeContent::Dir(dir) = &data.content {
+                    allowed &= dir.children.is_empty()
+                        || [SecurityMode::RX, SecurityMode::Write]
+                            .into_iter()
+                            .all(|mode| data.permits(user, mode));
+                }
+            });
+            if !allowed {
+                return Err(FsError::PermissionDenied);
+            }
+        }
+        parent.remove_child(name)?;
+        parent.borrow_mut().date = self.clock.now();
+        Ok(())
+    }
+
+    /// Moves the node at `src` to `dest` (see [`Self::target_path`]),
+    /// replacing a file, or an empty directory, already there.
+    pub fn rename(&self, src: &Path, dest: &Path) -> FsResult {
+        let node = self.lookup(src)?;
+        let name = src.basename().ok_or(FsError::PermissionDenied)?;
+        let src_parent = self.writable_parent(src)?;
+        let dest = self.target_path(src, dest);
+        if dest.0.starts_with(&src.0) {
+            return Err(FsError::InvalidArgument);
+        }
+        let new_name = dest.basename().ok_or(FsError::InvalidArgument)?;
+        let dest_parent = self.writable_parent(&dest)?;
+
+        if let Some(existing) = dest_parent.get_node(&Path::new(vec![new_name.clone()])) {
+            match (node.is_dir(), existing.as_dir()) {
+                (false, Some(_)) => return Err(FsError::IsDirectory),
+                (true, None) => return Err(FsError::NotDirectory),
+                (true, Some(dir)) if !dir.children.is_empty() => {
+                    return Err(FsError::DirectoryNotEmpty)
+                }
+                _ => {}
+            }
+            dest_parent.remove_child(&new_name)?;
+        }
+
+        src_parent.remove_child(name)?;
+        node.borrow_mut().name = new_name;
+        dest_parent.add_child(node)?;
+        let now = self.clock.now();
+        src_parent.borrow_mut().date = now;
+        dest_parent.borrow_mut().date = now;
+        Ok(())
+    }
+
+    /// Copies the node at `src` to `dest` (see [`Self::target_path`]), as a
+    /// new node owned by the current user. Directories are only copied if
+    /// `recursive`, and everything copied must be readable.
+    pub fn copy(&self, src: &Path, dest: &Path, recursive: bool) -> FsResult {
+        let node = self.lookup(src)?;
+        if node.is_dir() && !recursive {
+            return Err(FsError::IsDirectory);
+        }
+        let user = self.current_user();
+        let mut readable = true;
+        node.walk(&mut |n| {
+            let data = n.borrow();
+            let mode = if n.is_dir() {
+                SecurityMode::RX
+            } else {
+                SecurityMode::Read
+            };
+            readable &= data.permits(user, mode);
+        });
+        if !readable {
+            return Err(FsError::PermissionDenied);
+        }
+
+        let dest = self.target_path(src, dest);
+        if dest.0.starts_with(&src.0) {
+            return Err(FsError::InvalidArgument);
+        }
+        let name = dest.basename().ok_or(FsError::InvalidArgument)?;
+        let dest_parent = self.writable_parent(&dest)?;
+        let now = self.clock.now();
+
+        if let Some(existing) = dest_parent.get_node(&Path::new(vec![name.clone()])) {
+            if existing.is_dir() {
+                return Err(FsError::IsDirectory);
+            }
+            if node.is_dir() {
+                return Err(FsError::NotDirectory);
+            }
+            // copying over a file keeps the file, like writing to it
+            self.check_access(&existing, SecurityMode::Write)?;
+            let mut data = existing.borrow_mut();
+            data.content = node.borrow().content.clone();
+            data.date = now;
+            return Ok(());
+        }
+
+        let copy = node.deep_copy();
+        copy.walk(&mut |n| {
+            let mut data = n.borrow_mut();
+            data.owner = user.name.clone();
+            data.group = user.name.clone();
+            data.date = now;
+        });
+        copy.borrow_mut().name = name;
+        dest_parent.add_child(copy)?;
+        dest_parent.borrow_mut().date = now;
+        Ok(())
+    }
+
+    /// Expands the wildcard `pattern` (see [`glob`]) into the paths it
+    /// matches, written relative to the cwd the same way as the pattern.
+    /// Directories the current user cannot read are not searched.
+    pub fn glob(&self, pattern: &str) -> Vec<String> {
+        let (mut matches, rest) = match pattern.strip_prefix('/') {
+            Some(rest) => (vec!["/".to_string()], rest),
+            None => (vec![String::new()], pattern),
+        };
+
+        for component in rest.split('/') {
+            let join = |prefix: &str, name: &str| match prefix {
+                "" => name.to_string(),
+                prefix if prefix.ends_with('/') => format!("{prefix}{name}"),
+                prefix => format!("{prefix}/{name}"),
+            };
+
+            if !glob::is_pattern(component) {
+                let name = glob::unescape(component);
+                for prefix in &mut matches {
+                    *prefix = join(prefix, &name);
+                }
+                continue;
+            }
+
+            matches = matches
+                .iter()
+                .flat_map(|prefix| {
+                    let dir = match prefix.as_str() {
+                        "" => self.cwd.borrow().clone(),
+                        prefix => self.parse_path(prefix),
+                    };
+                    let children = self
+                        .lookup(&dir)
+                        .ok()
+                        .filter(|node| self.check_access(node, SecurityMode::Read).is_ok())
+                        .and_then(|node| node.as_dir())
+                        .map(|dir| dir.children)
+                        .unwrap_or_default();
+                    children
+                        .into_iter()
+                        .map(|child| child.borrow().name.clone())
+                        .filter(|name| glob::matches(component, name))
+                        .map(|name| join(prefix, &name))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        }
+
+        matches.retain(|path| self.lookup(&self.parse_path(path)).is_ok());
+        matches
+    }
+
+    /// Completes the partly typed path `word` with the entries of its
+    /// directory that start with its last component, written the same way as
+    /// `word`. Directories end with `/`. Hidden entries are only included when
+    /// the last component starts with `.`.
+    pub fn complete_path(&self, word: &str, dirs_only: bool) -> Vec<String> {
+        let (dir, prefix, base) = match word.rfind('/') {
+            Some(i) => (self.parse_path(&word[..=i]), &word[..=i], &word[i + 1..]),
+            None => (self.cwd.borrow().clone(), "", word),
+        };
+        let Some(dir) = self
+            .lookup(&dir)
+            .ok()
+            .filter(|node| self.check_access(node, SecurityMode::Read).is_ok())
+            .and_then(|node| node.as_dir())
+        else {
+            return Vec::new();
+        };
+
+        dir.children
+            .iter()
+            .filter_map(|child| {
+                let data = child.borrow();
+                let is_dir = matches!(data.content, NodeContent::Dir(_));
+                let hidden = data.name.starts_with('.') && !base.starts_with('.');
+                if !data.name.starts_with(base) || hidden || (dirs_only && !is_dir) {
+                    return None;
+                }
+                Some(format!(
+                    "{prefix}{}{}",
+                    data.name,
+                    if is_dir { "/" } else { "" }
+                ))
+            })
+            .collect()
+    }
+
+    /// Completes the name of an executable on the path.
+    pub fn complete_exe(&self, word: &str) -> Vec<String> {
+        let mut names = self
+            .exes()
+            .iter()
+            .map(|exe| exe.borrow().name.clone())
+            .filter(|name| name.starts_with(word))
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn history_path(&self) -> Path {
+        self.home().join(&path![HISTORY_FILE])
+    }
+
+    /// The current user's command history, oldest first.
+    pub fn history(&self) -> Vec<String> {
+        self.read_file(&self.history_path())
+            .map(|content| content.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Adds `line` to the end of the current user's command history.
+    pub fn add_history(&self, line: &str) -> FsResult {
+        self.write_file(&self.history_path(), &format!("{line}\n"), true)
+    }
+
+    /// Empties the current user's command history.
+    pub fn clear_history(&self) -> FsResult {
+        self.write_file(&self.history_path(), "", false)
+    }
+
     pub fn exes(&self) -> Vec<Node> {
         self.parsed_path()
             .iter()
-            .filter_map(|entry| self.root.get_dir(&Path::parse(&path![], entry)))
+            .filter_map(|entry| {
+                self.lookup(&Path::parse(&path![], &self.home(), entry))
+                    .ok()
+                    .and_then(|node| node.as_dir())
+            })
             .flat_map(|dir| dir.children)
             .filter_map(|node| {
                 let data = node.borrow();
@@ -241,9 +822,9 @@ impl ComputerBuilder {
         self.0
             .env
             .borrow_mut()
-            .entry("path".to_string())
+            .entry("PATH".to_string())
             .and_modify(|p| {
-                p.push(';');
+                p.push(':');
                 p.push_str(path.as_str());
             })
             .or_insert(path);
@@ -255,7 +836,118 @@ impl ComputerBuilder {
         self
     }
 
-    pub fn build(self) -> Computer {
+    /// Creates a home directory owned by each user that does not have one yet.
+    fn add_homes(self) -> Self {
+        let date = self.0.root.node.borrow().date;
+        for user in &self.0.users {
+            let home = user.home();
+            let parent = home.parent();
+            if self.0.root.get_node(&parent).is_none() {
+                let _ = self.0.root.node.add_node(
+                    &parent.parent(),
+                    Node::dir(parent.basename().unwrap_or_default(), date, Dir::empty()),
+                );
+            }
+            if self.0.root.get_node(&home).is_none() {
+                let others = if user.is_root() {
+                    Security::none()
+                } else {
+                    SecurityMode::RX.into()
+                };
+                let node = Node::dir(home.basename().unwrap_or_default(), date, Dir::empty())
+                    .owned_by(&user.name, &user.name)
+                    .with_security(SecurityMode::All, others, others);
+                let _ = self.0.root.node.add_node(&parent, node);
+            }
+        }
+        self
+    }
+
+    /// Adds a daemon process, started when the world was, for players to find
+    /// with `ps` and maybe kill.
+    pub fn daemon<T: ToString, U: ToString>(self, owner: T, cmdline: U) -> Self {
+        let date = self.0.root.node.borrow().date;
         self.0
+            .processes
+            .borrow_mut()
+            .spawn(owner, cmdline, date, true);
+        self
+    }
+
+    /// Adds a daemon like [`Self::daemon`] that uses `cpu` percent of the CPU
+    /// and `memory` KiB, standing out in `top`.
+    pub fn busy_daemon<T: ToString, U: ToString>(
+        self,
+        owner: T,
+        cmdline: U,
+        cpu: f32,
+        memory: u64,
+    ) -> Self {
+        let date = self.0.root.node.borrow().date;
+        let mut processes = self.0.processes.borrow_mut();
+        let pid = processes.spawn(owner, cmdline, date, true);
+        if let Some(process) = processes.get_mut(pid) {
+            process.cpu = cpu;
+            process.memory = memory;
+        }
+        drop(processes);
+        self
+    }
+
+    /// Sets the total memory, in KiB.
+    pub fn memory(mut self, memory: u64) -> Self {
+        self.0.memory = memory;
+        self
+    }
+
+    /// Gives `user` a startup file (see [`RC_FILE`]) in their home.
+    pub fn cmdrc<T: ToString>(self, user: &str, rc: T) -> Self {
+        let s = self.add_homes();
+        let Some(home) = s.0.find_user(user).map(User::home) else {
+            return s;
+        };
+        let date = s.0.root.node.borrow().date;
+        let node = Node::file(RC_FILE, date, File::new(rc)).owned_by(user, user);
+        let _ = s.0.root.node.add_node(&home, node);
+        s
+    }
+
+    /// Gives `user` a crontab (see [`crontab_path`]).
+    pub fn crontab<T: ToString>(self, user: &str, crontab: T) -> Self {
+        let path = crontab_path(user);
+        let date = self.0.root.node.borrow().date;
+        if let Ok(dir) = self.0.root.create_dirs(&path.parent(), date) {
+            let node = Node::file(user, date, File::new(crontab))
+                .owned_by(user, user)
+                .with_security(SecurityMode::RW, Security::none(), Security::none());
+            let _ = dir.add_child(node);
+        }
+        self
+    }
+
+    /// Adds a job that sends `message` to Unity on `schedule`, listed to
+    /// players as `command` run by `user`.
+    pub fn cron_message<T: Into<ipc::Message>>(
+        mut self,
+        schedule: &str,
+        user: &str,
+        command: &str,
+        message: T,
+    ) -> Self {
+        let mut job = CronJob::new(schedule, user, command).expect("Invalid cron schedule");
+        job.message = Some(message.into());
+        self.0.cron.push(job);
+        self
+    }
+
+    pub fn sudoers<T: ToString, It: IntoIterator<Item = T>>(mut self, sudoers: It) -> Self {
+        self.0.sudoers = sudoers.into_iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    pub fn build(self) -> Computer {
+        let computer = self.add_homes().0;
+        computer.write_user_files();
+        computer
     }
 }
@@ -0,0 +1,453 @@
+//! Scheduled jobs, run by the computers as time passes.
+//!
+//! Jobs come from the world (see `ComputerBuilder::cron_message`) and from
+//! each user's crontab (see [`crontab_path`]). The shell runs whatever is due
+//! before each prompt, and mails any output to the job's user.
+
+use std::str::FromStr;
+
+use chrono::{Datelike, Timelike};
+
+use crate::{ipc, log, path};
+
+use super::{
+    computer::Computer,
+    fs::{File, Node, NodeContent, NodeDateTime, Path, Security, SecurityMode},
+    subprocess::Stdio,
+    Game,
+};
+
+/// How far back missed jobs are caught up on, in minutes. Each job runs at
+/// most once per catch-up, however many times it was due.
+const MAX_CATCH_UP: i64 = 24 * 60;
+
+/// When a job runs, as the five fields `minute hour day-of-month month
+/// day-of-week` of a crontab line, each a bit set of the values it allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether day-of-month and day-of-week were not `*`. When both are
+    /// restricted, a job runs on days matching either, as in cron.
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Schedule {
+    pub fn matches(&self, time: &NodeDateTime) -> bool {
+        let bit = |set: u64, value: u32| set & (1 << value) != 0;
+        let day = bit(self.days, time.day());
+        let weekday = bit(self.weekdays, time.weekday().num_days_from_sunday());
+        let day_matches = match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        };
+        bit(self.minutes, time.minute())
+            && bit(self.hours, time.hour())
+            && bit(self.months, time.month())
+            && day_matches
+    }
+}
+
+/// Parses one field: `*`, `n`, `a-b`, any of them with a `/step`, or a
+/// comma separated list of those.
+fn parse_field(field: &str, name: &str, min: u32, max: u32) -> Result<u64, String> {
+    let bad = || format!("bad {name} \"{field}\"");
+    let mut set = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u32>().map_err(|_| bad())?)),
+            None => (part, None),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (
+                start.parse().map_err(|_| bad())?,
+                end.parse().map_err(|_| bad())?,
+            ),
+            // `n/step` runs from n to the end
+            None => {
+                let start = range.parse().map_err(|_| bad())?;
+                (start, if step.is_some() { max } else { start })
+            }
+        };
+        if start < min || end > max || start > end || step == Some(0) {
+            return Err(bad());
+        }
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    /// Parses the five schedule fields of a crontab line, or a shorthand like
+    /// `@daily`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = match s {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            s if s.starts_with('@') => return Err(format!("bad schedule \"{s}\"")),
+            s => s,
+        };
+        let [minutes, hours, days, months, weekdays] = s
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| "expected 5 schedule fields".to_string())?;
+        let mut weekday_set = parse_field(weekdays, "day of week", 0, 7)?;
+        // both 0 and 7 are Sunday
+        if weekday_set & (1 << 7) != 0 {
+            weekday_set |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minutes, "minute", 0, 59)?,
+            hours: parse_field(hours, "hour", 0, 23)?,
+            days: parse_field(days, "day of month", 1, 31)?,
+            months: parse_field(months, "month", 1, 12)?,
+            weekdays: weekday_set,
+            days_restricted: days != "*",
+            weekdays_restricted: weekdays != "*",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CronJob {
+    /// The schedule as written, e.g. `*/5 * * * *`.
+    pub spec: String,
+    pub schedule: Schedule,
+    pub user: String,
+    pub command: String,
+    /// Sent to Unity instead of running `command`, for jobs of the world
+    /// that drive the game. `command` is then only what players see.
+    pub message: Option<ipc::Message>,
+}
+
+impl CronJob {
+    pub fn new(spec: &str, user: &str, command: &str) -> Result<Self, String> {
+        Ok(Self {
+            spec: spec.to_string(),
+            schedule: spec.parse()?,
+            user: user.to_string(),
+            command: command.to_string(),
+            message: None,
+        })
+    }
+}
+
+/// Parses a crontab of `user`: one job per line, as its schedule then its
+/// command. Blank lines, `#` comments and `NAME=value` lines are skipped.
+/// Yields each job with its line number, or why the line is invalid.
+pub fn parse_crontab<'a>(
+    user: &'a str,
+    text: &'a str,
+) -> impl Iterator<Item = (usize, Result<CronJob, String>)> + 'a {
+    text.lines().enumerate().filter_map(move |(i, line)| {
+        let line = line.trim();
+        let first = line.split_whitespace().next()?;
+        if first.starts_with('#') || first.contains('=') {
+            return None;
+        }
+
+        let fields = if first.starts_with('@') { 1 } else { 5 };
+        let mut rest = line;
+        for _ in 0..fields {
+            match rest.split_once(char::is_whitespace) {
+                Some((_, r)) => rest = r.trim_start(),
+                None => return Some((i + 1, Err("missing command".to_string()))),
+            }
+        }
+        let spec = line[..line.len() - rest.len()].trim_end();
+        Some((i + 1, CronJob::new(spec, user, rest)))
+    })
+}
+
+/// Where `user`'s crontab is kept.
+pub fn crontab_path(user: &str) -> Path {
+    path!["var", "spool", "cron", user]
+}
+
+/// Where `user`'s mailbox is kept.
+pub fn mail_path(user: &str) -> Path {
+    path!["var", "mail", user]
+}
+
+impl Computer {
+    /// The content of `user`'s crontab, if they have one. Read as cron does,
+    /// whatever the permissions of the current user.
+    pub fn crontab(&self, user: &str) -> Option<String> {
+        let node = self.root.get_node(&crontab_path(user))?;
+        let content = match &node.borrow().content {
+            NodeContent::File(f) => Some(f.content.clone()),
+            _ => None,
+        };
+        content
+    }
+
+    /// Replaces `user`'s crontab with `content`, or removes it if `None`.
+    /// Returns whether there was one before.
+    pub fn set_crontab(&self, user: &str, content: Option<&str>) -> bool {
+        let path = crontab_path(user);
+        let date = self.clock.now();
+        let Ok(dir) = self.root.create_dirs(&path.parent(), date) else {
+            return false;
+        };
+        let existed = match &mut dir.borrow_mut().content {
+            NodeContent::Dir(d) => match d.get_child_index(user) {
+                Some(index) => {
+                    d.children.remove(index);
+                    true
+                }
+                None => false,
+            },
+            _ => return false,
+        };
+        if let Some(content) = content {
+            let node = Node::file(user, date, File::new(content))
+                .owned_by(user, user)
+                .with_security(SecurityMode::RW, Security::none(), Security::none());
+            let _ = dir.add_child(node);
+        }
+        existed
+    }
+
+    /// Every valid job of the world and of the users' crontabs.
+    pub fn cron_jobs(&self) -> Vec<CronJob> {
+        let mut jobs = self.cron.clone();
+        for user in &self.users {
+            if let Some(crontab) = self.crontab(&user.name) {
+                jobs.extend(parse_crontab(&user.name, &crontab).filter_map(|(_, job)| job.ok()));
+            }
+        }
+        jobs
+    }
+
+    /// Appends a message to `user`'s mailbox, creating it if needed.
+    fn deliver_mail(&self, user: &str, subject: &str, body: &str, date: NodeDateTime) {
+        let Ok(dir) = self.root.create_dirs(&mail_path(user).parent(), date) else {
+            return;
+        };
+        let mail = format!(
+            "From cron@{} {}\nSubject: {subject}\n\n{body}\n",
+            self.name,
+            date.format("%a %b %e %H:%M:%S %Y"),
+        );
+        match dir.as_dir().and_then(|d| d.get_child(user)) {
+            Some(node) => {
+                let mut data = node.borrow_mut();
+                if let NodeContent::File(f) = &mut data.content {
+                    f.content.push_str(&mail);
+                }
+                data.date = date;
+            }
+            None => {
+                let node = Node::file(user, date, File::new(mail))
+                    .owned_by(user, user)
+                    .with_security(SecurityMode::RW, Security::none(), Security::none());
+                let _ = dir.add_child(node);
+            }
+        }
+    }
+}
+
+impl Game {
+    /// Runs the jobs of every computer that became due since the last call.
+    /// Cheap when no minute has passed, so full-screen subprocesses call it
+    /// as they wait for keys, as well as the prompt.
+    pub fn run_cron(&self) {
+        let now = self.clock.now();
+        let now = now
+            .with_second(0)
+            .unwrap_or(now)
+            .with_nanosecond(0)
+            .unwrap_or(now);
+        let last = self.last_cron.replace(now);
+        let since = last.max(now - chrono::Duration::minutes(MAX_CATCH_UP));
+
+        let minutes = (1..=(now - since).num_minutes())
+            .map(|i| since + chrono::Duration::minutes(i))
+            .collect::<Vec<_>>();
+        if minutes.is_empty() {
+            return;
+        }
+        for (index, computer) in self.computers.iter().enumerate() {
+            for job in computer.cron_jobs() {
+                if minutes.iter().any(|time| job.schedule.matches(time)) {
+                    let got_mail = self.run_cron_job(index, &job, now);
+                    if got_mail
+                        && index == self.current_computer_index()
+                        && job.user == computer.current_user().name
+                    {
+                        self.new_mail.set(true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether cron mailed the current user since the last call, for the
+    /// prompt to say so.
+    pub fn take_new_mail(&self) -> bool {
+        self.new_mail.take()
+    }
+
+    /// Runs `job` on the computer at `index` as its user, from their home,
+    /// and mails them its output. Returns whether there was any.
+    fn run_cron_job(&self, index: usize, job: &CronJob, now: NodeDateTime) -> bool {
+        if let Some(message) = &job.message {
+            if let Err(e) = self.connection.borrow_mut().write_message(message.clone()) {
+                log!("Could not send message of cron job {}: {e:?}", job.command);
+            }
+            return false;
+        }
+
+        let computer = &self.computers[index];
+        let Some(user_index) = computer.find_user_index(&job.user) else {
+            return false;
+        };
+
+        // jobs must not disturb the player's session
+        let previous_computer = self.current_computer_index.replace(index);
+        let previous_user = computer.current_user_index.replace(user_index);
+        let previous_cwd = computer.cwd.replace(computer.current_user().home());
+        let previous_env = computer.env.borrow().clone();
+
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let res = self.start_exe_from_path(
+            "cmd",
+            vec!["-c".to_string(), job.command.clone()],
+            &mut Stdio {
+                stdin: &mut std::io::empty(),
+                stdout: &mut stdout,
+                stderr: &mut stderr,
+            },
+        );
+
+        computer.env.replace(previous_env);
+        computer.cwd.replace(previous_cwd);
+        computer.current_user_index.set(previous_user);
+        self.current_computer_index.set(previous_computer);
+
+        let mut output = String::from_utf8_lossy(&stdout).into_owned();
+        output.push_str(&String::from_utf8_lossy(&stderr));
+        match res {
+            Ok(Err(e)) => output.push_str(&format!("cmd: {e}\n")),
+            Err(e) => output.push_str(&format!("cmd: {e}\n")),
+            Ok(Ok(_)) => {}
+        }
+        if output.is_empty() {
+            return false;
+        }
+        let subject = format!("Cron <{}@{}> {}", job.user, computer.name, job.command);
+        computer.deliver_mail(&job.user, &subject, &output, now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date;
+
+    fn schedule(s: &str) -> Schedule {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(parse_field("*", "hour", 0, 23), Ok((1 << 24) - 1));
+        assert_eq!(parse_field("5", "minute", 0, 59), Ok(1 << 5));
+        assert_eq!(parse_field("1-3", "month", 1, 12), Ok(0b1110));
+        assert_eq!(parse_field("1,4,6", "month", 1, 12), Ok(0b101_0010));
+        assert_eq!(parse_field("1-3,10", "month", 1, 12), Ok(0b100_0000_1110));
+    }
+
+    #[test]
+    fn steps() {
+        let every_5 = (0..60).step_by(5).fold(0, |set, i| set | 1 << i);
+        assert_eq!(parse_field("*/5", "minute", 0, 59), Ok(every_5));
+        assert_eq!(parse_field("0-59/5", "minute", 0, 59), Ok(every_5));
+        assert_eq!(
+            parse_field("10-20/5", "minute", 0, 59),
+            Ok(1 << 10 | 1 << 15 | 1 << 20)
+        );
+        // `n/step` runs from n to the end
+        assert_eq!(parse_field("20/20", "minute", 0, 59), Ok(1 << 20 | 1 << 40));
+    }
+
+    #[test]
+    fn invalid_fields() {
+        for field in ["", "x", "60", "5-1", "1-", "*/0", "*/x", "1,,2", "-1"] {
+            assert_eq!(
+                parse_field(field, "minute", 0, 59),
+                Err(format!("bad minute \"{field}\"")),
+            );
+        }
+        assert!(parse_field("0", "day of month", 1, 31).is_err());
+        assert!(parse_field("13", "month", 1, 12).is_err());
+    }
+
+    #[test]
+    fn schedules() {
+        assert!("* * * *".parse::<Schedule>().is_err());
+        assert!("* * * * * *".parse::<Schedule>().is_err());
+        assert!("* 24 * * *".parse::<Schedule>().is_err());
+        assert!("@often".parse::<Schedule>().is_err());
+        assert_eq!(schedule("@daily"), schedule("0 0 * * *"));
+        assert_eq!(schedule("@hourly"), schedule("0 * * * *"));
+    }
+
+    #[test]
+    fn matching() {
+        // a Friday
+        let time = date!["12 Jan 2024 12:30"];
+        assert!(schedule("* * * * *").matches(&time));
+        assert!(schedule("*/5 * * * *").matches(&time));
+        assert!(!schedule("*/7 * * * *").matches(&time));
+        assert!(schedule("30 12 12 1 *").matches(&time));
+        assert!(!schedule("30 13 * * *").matches(&time));
+        assert!(schedule("30 12 * * 1-5").matches(&time));
+        assert!(!schedule("30 12 * * 0,6").matches(&time));
+        // with both days restricted, either matches
+        assert!(schedule("30 12 1 * 5").matches(&time));
+        assert!(schedule("30 12 12 * 1").matches(&time));
+        assert!(!schedule("30 12 1 * 1").matches(&time));
+        // both 0 and 7 are Sunday
+        let sunday = date!["14 Jan 2024 00:00"];
+        assert!(schedule("0 0 * * 0").matches(&sunday));
+        assert!(schedule("0 0 * * 7").matches(&sunday));
+        assert!(!schedule("0 0 * * 7").matches(&time));
+    }
+
+    #[test]
+    fn crontabs() {
+        let text = "# comment\n\nMAILTO=root\n*/5 * * * * echo hi  there\n@daily ls\n* * *\n";
+        let jobs = parse_crontab("user", text).collect::<Vec<_>>();
+        assert_eq!(jobs.len(), 3);
+
+        let (line, job) = &jobs[0];
+        let job = job.as_ref().unwrap();
+        assert_eq!(*line, 4);
+        assert_eq!(job.spec, "*/5 * * * *");
+        assert_eq!(job.command, "echo hi  there");
+        assert_eq!(job.user, "user");
+
+        let (line, job) = &jobs[1];
+        assert_eq!(*line, 5);
+        assert_eq!(job.as_ref().unwrap().command, "ls");
+
+        let (line, job) = &jobs[2];
+        assert_eq!(*line, 6);
+        assert_eq!(job.as_ref().unwrap_err(), "missing command");
+    }
+}
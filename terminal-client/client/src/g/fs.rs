@@ -1,5 +1,6 @@
 use bitmask::bitmask;
 
+use super::computer::User;
 use super::subprocess::SubprocessFn;
 use std::cell::{Ref, RefCell, RefMut};
 use std::fmt::Debug;
@@ -16,12 +17,43 @@ macro_rules! date {
 pub enum FsError {
     AlreadyExists,
     DoesNotExist,
+    IsDirectory,
     NotDirectory,
+    NotFile,
     NotExecutable,
+    PermissionDenied,
+    DirectoryNotEmpty,
+    /// E.g. moving a directory into itself.
+    InvalidArgument,
+}
+
+impl std::fmt::Display for FsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FsError::AlreadyExists => "File exists",
+            FsError::DoesNotExist => "No such file or directory",
+            FsError::IsDirectory => "Is a directory",
+            FsError::NotDirectory => "Not a directory",
+            FsError::NotFile => "Not a regular file",
+            FsError::NotExecutable => "Not executable",
+            FsError::PermissionDenied => "Permission denied",
+            FsError::DirectoryNotEmpty => "Directory not empty",
+            FsError::InvalidArgument => "Invalid argument",
+        })
+    }
 }
 
 pub type FsResult<R = ()> = Result<R, FsError>;
 
+/// The home directory of the user `name`: `/root` for root, `/home/<name>` otherwise.
+pub fn home_dir(name: &str) -> Path {
+    if name == "root" {
+        Path::new(vec!["root".to_string()])
+    } else {
+        Path::new(vec!["home".to_string(), name.to_string()])
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct Path(pub Vec<String>);
 
@@ -37,11 +69,20 @@ impl Path {
         Self(path.into())
     }
 
-    pub fn parse(cwd: &Path, relative: &str) -> Self {
-        let mut new_path = if relative.starts_with('/') {
-            Vec::new()
+    /// Resolves `relative` against `cwd`.
+    /// A leading `~` expands to `home`, and `~name` to the home directory of `name`.
+    pub fn parse(cwd: &Path, home: &Path, relative: &str) -> Self {
+        let (mut new_path, relative) = if let Some(rest) = relative.strip_prefix('~') {
+            let (user, rest) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            if user.is_empty() {
+                (home.0.clone(), rest)
+            } else {
+                (home_dir(user).0, rest)
+            }
+        } else if relative.starts_with('/') {
+            (Vec::new(), relative)
         } else {
-            cwd.0.clone()
+            (cwd.0.clone(), relative)
         };
 
         new_path.extend(relative.split('/').map(|s| s.to_string()));
@@ -146,6 +187,11 @@ impl Dir {
         }
     }
 
+    pub fn remove_child<T: ToString>(&mut self, name: T) -> FsResult<Node> {
+        let index = self.get_child_index(name).ok_or(FsError::DoesNotExist)?;
+        Ok(self.children.remove(index))
+    }
+
     fn sort(&mut self) {
         self.children
             .sort_by(|a, b| (*a.0).borrow().name.cmp(&(*b.0).borrow().name));
@@ -157,6 +203,7 @@ pub struct Root {
     pub node: Node,
 }
 
+#[allow(dead_code)]
 impl Root {
     pub fn new<U>(children: U) -> Self
     where
@@ -178,6 +225,46 @@ impl Root {
     pub fn get_dir(&self, path: &Path) -> Option<Dir> {
         self.get_node(path).and_then(|node| node.as_dir())
     }
+
+    /// Returns the directory at `path`, first creating it and any missing
+    /// parents, owned by root and dated `date`.
+    pub fn create_dirs(&self, path: &Path, date: NodeDateTime) -> FsResult<Node> {
+        let mut node = self.node.clone();
+        for name in &path.0 {
+            let dir = node.as_dir().ok_or(FsError::NotDirectory)?;
+            node = match dir.get_child(name) {
+                Some(child) => child,
+                None => {
+                    let child = Node::dir(name, date, Dir::empty());
+                    node.add_child(child.clone())?;
+                    child
+                }
+            };
+        }
+        if node.is_dir() {
+            Ok(node)
+        } else {
+            Err(FsError::NotDirectory)
+        }
+    }
+
+    /// The path of every node but the root, each directory before what is in
+    /// it.
+    pub fn index(&self) -> Vec<Path> {
+        fn index_children(node: &Node, path: &Path, paths: &mut Vec<Path>) {
+            if let NodeContent::Dir(dir) = &node.borrow().content {
+                for child in &dir.children {
+                    let path = path.clone().join(&path![child.borrow().name.clone()]);
+                    paths.push(path.clone());
+                    index_children(child, &path, paths);
+                }
+            }
+        }
+
+        let mut paths = Vec::new();
+        index_children(&self.node, &Path::default(), &mut paths);
+        paths
+    }
 }
 
 pub type Executable = &'static dyn SubprocessFn;
@@ -195,10 +282,34 @@ bitmask! {
         Write 	= 2,
         Execute = 4,
         RW 		= 1 | 2,
+        RX 		= 1 | 4,
         All 	= 7,
     }
 }
 
+impl Security {
+    pub fn from_mask(mask: u8) -> Self {
+        Security {
+            mask: mask & *Security::all(),
+        }
+    }
+
+    /// Parses a single octal permission digit, e.g. the `5` in `755`.
+    pub fn from_octal(digit: u8) -> Self {
+        let mut security = Security::none();
+        if digit & 4 != 0 {
+            security.set(SecurityMode::Read);
+        }
+        if digit & 2 != 0 {
+            security.set(SecurityMode::Write);
+        }
+        if digit & 1 != 0 {
+            security.set(SecurityMode::Execute);
+        }
+        security
+    }
+}
+
 impl std::fmt::Display for Security {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -243,10 +354,32 @@ pub struct NodeData {
     pub date: NodeDateTime,
     pub content: NodeContent,
 
+    pub owner: String,
+    pub group: String,
+
     pub owner_security: Security,
+    pub group_security: Security,
     pub other_security: Security,
 }
 
+impl NodeData {
+    /// The permission bits that apply to `user` for this node.
+    pub fn security_for(&self, user: &User) -> Security {
+        if user.name == self.owner {
+            self.owner_security
+        } else if user.in_group(&self.group) {
+            self.group_security
+        } else {
+            self.other_security
+        }
+    }
+
+    /// Whether `user` may access this node with `mode`. Root may access anything.
+    pub fn permits(&self, user: &User, mode: SecurityMode) -> bool {
+        user.is_root() || self.security_for(user).contains(mode)
+    }
+}
+
 #[allow(dead_code)]
 impl Node {
     pub fn data(&self) -> Ref<'_, NodeData> {
@@ -279,8 +412,12 @@ impl Node {
             date,
             content: NodeContent::Dir(dir),
 
-            owner_security: SecurityMode::RW.into(),
-            other_security: SecurityMode::Read.into(),
+            owner: "root".to_string(),
+            group: "root".to_string(),
+
+            owner_security: SecurityMode::All.into(),
+            group_security: SecurityMode::RX.into(),
+            other_security: SecurityMode::RX.into(),
         })
     }
 
@@ -290,7 +427,11 @@ impl Node {
             date,
             content: NodeContent::File(file),
 
+            owner: "root".to_string(),
+            group: "root".to_string(),
+
             owner_security: SecurityMode::RW.into(),
+            group_security: SecurityMode::Read.into(),
             other_security: SecurityMode::Read.into(),
         })
     }
@@ -305,11 +446,39 @@ impl Node {
             date,
             content: NodeContent::Executable(subprocess),
 
+            owner: "root".to_string(),
+            group: "root".to_string(),
+
             owner_security: SecurityMode::Execute.into(),
+            group_security: SecurityMode::Execute.into(),
             other_security: SecurityMode::Execute.into(),
         })
     }
 
+    pub fn owned_by<T: ToString, U: ToString>(self, owner: T, group: U) -> Self {
+        {
+            let mut data = self.borrow_mut();
+            data.owner = owner.to_string();
+            data.group = group.to_string();
+        }
+        self
+    }
+
+    pub fn with_security<T, U, V>(self, owner: T, group: U, other: V) -> Self
+    where
+        T: Into<Security>,
+        U: Into<Security>,
+        V: Into<Security>,
+    {
+        {
+            let mut data = self.borrow_mut();
+            data.owner_security = owner.into();
+            data.group_security = group.into();
+            data.other_security = other.into();
+        }
+        self
+    }
+
     pub fn is_dir(&self) -> bool {
         matches!(&self.borrow().content, NodeContent::Dir(_))
     }
@@ -362,6 +531,33 @@ impl Node {
         }
     }
 
+    pub fn remove_child<T: ToString>(&self, name: T) -> FsResult<Node> {
+        match &mut self.borrow_mut().content {
+            NodeContent::Dir(dir) => dir.remove_child(name),
+            _ => Err(FsError::NotDirectory),
+        }
+    }
+
+    /// Copies this node and, for a directory, everything in it, so that the
+    /// copy can be changed without changing the original.
+    pub fn deep_copy(&self) -> Node {
+        let mut data = self.borrow().clone();
+        if let NodeContent::Dir(dir) = &mut data.content {
+            dir.children = dir.children.iter().map(Node::deep_copy).collect();
+        }
+        Node::new(data)
+    }
+
+    /// Calls `f` on this node and, for a directory, everything in it.
+    pub fn walk(&self, f: &mut impl FnMut(&Node)) {
+        f(self);
+        if let NodeContent::Dir(dir) = &self.borrow().content {
+            for child in &dir.children {
+                child.walk(f);
+            }
+        }
+    }
+
     pub fn add_node(&self, dir: &Path, node: Node) -> FsResult<()> {
         match self.get_node(dir) {
             Some(dir) => dir.add_child(node).and(Ok(())),
@@ -0,0 +1,180 @@
+//! Shell wildcard patterns: `*`, `?` and `[abc]`.
+//!
+//! Patterns are strings in which a backslash makes the next character
+//! literal, so that quoted wildcards can be told apart from active ones.
+
+const SPECIAL: &[char] = &['*', '?', '[', ']', '\\'];
+
+/// Escapes `ch` if it would otherwise have a meaning in a pattern.
+pub fn push_literal(pattern: &mut String, ch: char) {
+    if SPECIAL.contains(&ch) {
+        pattern.push('\\');
+    }
+    pattern.push(ch);
+}
+
+/// Whether `pattern` contains any unescaped wildcard.
+pub fn is_pattern(pattern: &str) -> bool {
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '*' | '?' | '[' => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Removes the escapes from `pattern`, giving back the text as typed.
+pub fn unescape(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => out.extend(chars.next()),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Whether the file name `name` matches `pattern`.
+/// Names starting with `.` are only matched by a pattern starting with `.`.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') && !pattern.starts_with('.') && !pattern.starts_with("\\.") {
+        return false;
+    }
+    matches_hidden(pattern, name)
+}
+
+/// Like [`matches`], but wildcards also match a leading `.`, as in
+/// `find -name`.
+pub fn matches_hidden(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    matches_from(&pattern, &name)
+}
+
+fn matches_from(pattern: &[char], name: &[char]) -> bool {
+    match pattern {
+        [] => name.is_empty(),
+        ['*', rest @ ..] => (0..=name.len()).any(|i| matches_from(rest, &name[i..])),
+        ['?', rest @ ..] => !name.is_empty() && matches_from(rest, &name[1..]),
+        ['[', rest @ ..] => match (name.first(), match_class(rest)) {
+            (Some(&ch), Some((class, rest))) => class(ch) && matches_from(rest, &name[1..]),
+            // an unclosed `[` is literal
+            (Some('['), None) => matches_from(rest, &name[1..]),
+            _ => false,
+        },
+        ['\\', ch, rest @ ..] | [ch, rest @ ..] => {
+            name.first() == Some(ch) && matches_from(rest, &name[1..])
+        }
+    }
+}
+
+/// Parses a character class after its `[`: `abc`, `a-z`, or negated with a
+/// leading `!` or `^`. Returns a predicate and what follows the closing `]`.
+#[allow(clippy::type_complexity)]
+fn match_class(pattern: &[char]) -> Option<(Box<dyn Fn(char) -> bool + '_>, &[char])> {
+    let (negated, pattern) = match pattern {
+        ['!' | '^', rest @ ..] => (true, rest),
+        _ => (false, pattern),
+    };
+    // a `]` right at the start is part of the class
+    let end = pattern
+        .iter()
+        .skip(1)
+        .position(|&ch| ch == ']')
+        .map(|i| i + 1)?;
+    let class = &pattern[..end];
+
+    let predicate = move |ch: char| {
+        let mut i = 0;
+        let mut found = false;
+        while i < class.len() {
+            if class[i] == '\\' && i + 1 < class.len() {
+                i += 1;
+            }
+            if i + 2 < class.len() && class[i + 1] == '-' {
+                found |= (class[i]..=class[i + 2]).contains(&ch);
+                i += 3;
+            } else {
+                found |= class[i] == ch;
+                i += 1;
+            }
+        }
+        found != negated
+    };
+    Some((Box::new(predicate), &pattern[end + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_matches_any_run() {
+        assert!(matches("*", "file"));
+        assert!(matches("*.log", "intrusions.log"));
+        assert!(matches("a*b*c", "aXXbYc"));
+        assert!(matches("log*", "log"));
+        assert!(!matches("*.log", "intrusions.log.1"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_char() {
+        assert!(matches("?", "a"));
+        assert!(matches("file?", "file1"));
+        assert!(!matches("file?", "file"));
+        assert!(!matches("file?", "file12"));
+    }
+
+    #[test]
+    fn classes() {
+        assert!(matches("[abc]", "b"));
+        assert!(!matches("[abc]", "d"));
+        assert!(matches("file[0-9]", "file7"));
+        assert!(!matches("file[0-9]", "filex"));
+        assert!(matches("[!abc]", "d"));
+        assert!(matches("[^abc]", "d"));
+        assert!(!matches("[!abc]", "a"));
+        assert!(matches("[]x]", "]"));
+        // an unclosed `[` is literal
+        assert!(matches("a[b", "a[b"));
+    }
+
+    #[test]
+    fn escapes_are_literal() {
+        assert!(matches("\\*", "*"));
+        assert!(!matches("\\*", "a"));
+        assert!(matches("a\\?", "a?"));
+        assert!(!is_pattern("\\*\\?\\["));
+        assert!(is_pattern("a\\*b*"));
+        assert_eq!(unescape("a\\*b\\\\"), "a*b\\");
+    }
+
+    #[test]
+    fn push_literal_round_trips() {
+        let mut pattern = String::new();
+        for ch in "[x]*?\\".chars() {
+            push_literal(&mut pattern, ch);
+        }
+        assert!(!is_pattern(&pattern));
+        assert_eq!(unescape(&pattern), "[x]*?\\");
+        assert!(matches(&pattern, "[x]*?\\"));
+    }
+
+    #[test]
+    fn hidden_files_need_a_leading_dot() {
+        assert!(!matches("*", ".cmdrc"));
+        assert!(!matches("?cmdrc", ".cmdrc"));
+        assert!(!matches("[.]cmdrc", ".cmdrc"));
+        assert!(matches(".*", ".cmdrc"));
+        assert!(matches("\\.cmdrc", ".cmdrc"));
+        assert!(matches_hidden("*", ".cmdrc"));
+        assert!(matches_hidden("?cmdrc", ".cmdrc"));
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+pub const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Number of mixing rounds for strong hashes.
+/// Kept low so brute forcing small search spaces stays computable in-game.
+const STRONG_ROUNDS: usize = 48;
+
+pub fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(seed, |h, b| (h ^ *b as u64).wrapping_mul(FNV_PRIME))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashKind {
+    /// Unsalted 32 bit digest, like old MD5 crypt. Formatted `$1$<digest>`.
+    Weak,
+    /// Salted, iterated 64 bit digest. Formatted `$6$<salt>$<digest>`.
+    Strong,
+}
+
+impl HashKind {
+    /// How many guesses per in-game second a cracker can try against this kind of hash.
+    pub fn guesses_per_second(&self) -> f64 {
+        match self {
+            HashKind::Weak => 2_000_000.0,
+            HashKind::Strong => 1_500.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordHash {
+    pub kind: HashKind,
+    pub salt: String,
+    pub digest: u64,
+}
+
+impl PasswordHash {
+    pub fn weak(password: &str) -> Self {
+        let h = fnv1a(FNV_OFFSET, password.as_bytes());
+        Self {
+            kind: HashKind::Weak,
+            salt: String::new(),
+            digest: (h ^ (h >> 32)) & 0xffff_ffff,
+        }
+    }
+
+    pub fn strong(password: &str, salt: &str) -> Self {
+        let mut h = fnv1a(fnv1a(FNV_OFFSET, salt.as_bytes()), password.as_bytes());
+        for _ in 0..STRONG_ROUNDS {
+            h = fnv1a(h, password.as_bytes());
+            h ^= h >> 29;
+        }
+        Self {
+            kind: HashKind::Strong,
+            salt: salt.to_string(),
+            digest: h,
+        }
+    }
+
+    /// A salt derived from `seed`, so that generated worlds are reproducible.
+    pub fn salt_from(seed: &str) -> String {
+        format!("{:08x}", fnv1a(FNV_OFFSET, seed.as_bytes()) as u32)
+    }
+
+    pub fn new(kind: HashKind, password: &str, salt: &str) -> Self {
+        match kind {
+            HashKind::Weak => Self::weak(password),
+            HashKind::Strong => Self::strong(password, salt),
+        }
+    }
+
+    pub fn verify(&self, password: &str) -> bool {
+        *self == Self::new(self.kind, password, &self.salt)
+    }
+}
+
+impl Display for PasswordHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            HashKind::Weak => write!(f, "$1${:08x}", self.digest),
+            HashKind::Strong => write!(f, "$6${}${:016x}", self.salt, self.digest),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseHashError;
+
+impl FromStr for PasswordHash {
+    type Err = ParseHashError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('$').collect();
+        let digest = |hex: &str| u64::from_str_radix(hex, 16).map_err(|_| ParseHashError);
+        match parts[..] {
+            ["", "1", hex] => Ok(Self {
+                kind: HashKind::Weak,
+                salt: String::new(),
+                digest: digest(hex)?,
+            }),
+            ["", "6", salt, hex] => Ok(Self {
+                kind: HashKind::Strong,
+                salt: salt.to_string(),
+                digest: digest(hex)?,
+            }),
+            _ => Err(ParseHashError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weak_round_trip() {
+        let hash = PasswordHash::weak("123456");
+        let text = hash.to_string();
+        assert!(text.starts_with("$1$"));
+        assert_eq!(text.len(), 3 + 8);
+
+        let parsed = text.parse::<PasswordHash>().unwrap();
+        assert_eq!(parsed, hash);
+        assert!(parsed.verify("123456"));
+        assert!(!parsed.verify("1234567"));
+        assert!(!parsed.verify(""));
+    }
+
+    #[test]
+    fn strong_round_trip() {
+        let hash = PasswordHash::strong("hunter2", "salt");
+        let text = hash.to_string();
+        assert!(text.starts_with("$6$salt$"));
+        assert_eq!(text.len(), 8 + 16);
+
+        let parsed = text.parse::<PasswordHash>().unwrap();
+        assert_eq!(parsed, hash);
+        assert_eq!(parsed.salt, "salt");
+        assert!(parsed.verify("hunter2"));
+        assert!(!parsed.verify("hunter3"));
+    }
+
+    #[test]
+    fn salt_changes_digest() {
+        let a = PasswordHash::strong("hunter2", "a");
+        let b = PasswordHash::strong("hunter2", "b");
+        assert_ne!(a.digest, b.digest);
+        assert_eq!(
+            PasswordHash::salt_from("seed"),
+            PasswordHash::salt_from("seed")
+        );
+        assert_eq!(PasswordHash::salt_from("seed").len(), 8);
+    }
+
+    #[test]
+    fn invalid_hashes() {
+        for s in [
+            "",
+            "123456",
+            "$1$",
+            "$1$xyz",
+            "$1$salt$00",
+            "$6$00",
+            "$6$salt$",
+            "$6$salt$xyz",
+            "$5$salt$00",
+            "1$00",
+        ] {
+            assert!(s.parse::<PasswordHash>().is_err(), "{s}");
+        }
+    }
+}
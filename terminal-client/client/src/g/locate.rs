@@ -0,0 +1,70 @@
+//! The index of every path in a computer's fs, searched by `locate`.
+//!
+//! Like the real one, the index is a file, rebuilt by `updatedb` and so only
+//! as fresh as its last run; each computer's is built when the world is.
+
+use crate::path;
+
+use super::{
+    computer::Computer,
+    fs::{File, FsError, FsResult, Node, NodeContent, Path, SecurityMode},
+};
+
+/// Where the index is kept.
+pub fn locate_db_path() -> Path {
+    path!["var", "lib", "locate", "db"]
+}
+
+impl Computer {
+    /// Records every path of the fs in the index, whatever the permissions
+    /// of the current user.
+    pub fn update_locate_db(&self) -> FsResult {
+        let path = locate_db_path();
+        let name = path.basename().ok_or(FsError::IsDirectory)?;
+        let date = self.clock.now();
+        let dir = self.root.create_dirs(&path.parent(), date)?;
+        let content = self
+            .root
+            .index()
+            .iter()
+            .map(|path| format!("{path}\n"))
+            .collect::<String>();
+
+        match dir.as_dir().and_then(|d| d.get_child(&name)) {
+            Some(node) => {
+                let mut data = node.borrow_mut();
+                match &mut data.content {
+                    NodeContent::File(f) => f.content = content,
+                    _ => return Err(FsError::NotFile),
+                }
+                data.date = date;
+            }
+            None => {
+                let node = Node::file(name, date, File::new(content))
+                    .owned_by("root", "root")
+                    .with_security(SecurityMode::RW, SecurityMode::Read, SecurityMode::Read);
+                dir.add_child(node)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The paths in the index that the current user may see, those in
+    /// directories they can list, or `None` if there is no index.
+    pub fn locate_db(&self) -> Option<Vec<String>> {
+        let node = self.root.get_node(&locate_db_path())?;
+        let content = node.as_file()?.content;
+        let visible = content
+            .lines()
+            .filter(|line| {
+                let path = Path::new(line.split('/').map(str::to_string).collect::<Vec<_>>());
+                let parent = path.normalized().parent();
+                self.lookup(&parent)
+                    .and_then(|dir| self.check_access(&dir, SecurityMode::RX))
+                    .is_ok()
+            })
+            .map(str::to_string)
+            .collect();
+        Some(visible)
+    }
+}
@@ -1,26 +1,55 @@
+pub mod clock;
 pub mod computer;
+pub mod cron;
 pub mod fs;
+pub mod glob;
+pub mod hash;
+pub mod locate;
+pub mod process;
+pub mod save;
 pub mod subprocess;
 
 use std::{
     cell::{Cell, RefCell},
     collections::{HashMap, VecDeque},
     rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
+use clock::Clock;
 pub use computer::Computer;
 use computer::{ComputerBuilder, ComputerId, User};
-use fs::{File, Node, Path};
+use fs::{File, Node, NodeDateTime, Path};
+use process::Pid;
+use subprocess::{ExitStatus, Stdio};
 
-use crate::{date, ipc, path};
+use crate::{date, ipc, log, path};
 
 pub struct Game {
     pub connection: Box<RefCell<dyn ipc::Connection>>,
     pub computers: Vec<Rc<Computer>>,
+    /// Shared by every computer.
+    pub clock: Rc<Clock>,
 
     current_computer_index: Cell<usize>,
     computer_address_map: HashMap<String, usize>,
     process_queue: RefCell<VecDeque<(String, Vec<String>)>>,
+    /// Set on Ctrl-C, see `interrupted`.
+    interrupted: Arc<AtomicBool>,
+    /// The processes currently running on the terminal, innermost last, with
+    /// the index of the computer they run on.
+    running: RefCell<Vec<(usize, Pid)>>,
+    /// When cron jobs were last run, see `run_cron`.
+    last_cron: Cell<NodeDateTime>,
+    /// Whether cron mailed the current user since the prompt last said so.
+    new_mail: Cell<bool>,
+    /// How many interactive sessions were started, see `start_session`.
+    sessions: Cell<u64>,
+    /// The id of the session that has the terminal, see `session`.
+    session: Cell<u64>,
 }
 
 impl Game {
@@ -28,11 +57,7 @@ impl Game {
         connection: Box<RefCell<dyn ipc::Connection>>,
         initial_computer: ComputerId,
     ) -> Self {
-        let default_exes = std::iter::empty()
-            .chain(subprocess::sys::DEFAULT)
-            .chain(subprocess::fs::DEFAULT)
-            .chain(subprocess::myhealth::DEFAULT)
-            .cloned();
+        let default_exes = subprocess::all().cloned();
 
         let computers = vec![
             ComputerBuilder::new()
@@ -53,13 +78,31 @@ impl Game {
                     date!["12 Jan 2024 12:30"],
                     default_exes.clone(),
                 )
+                .cmdrc(
+                    "root",
+                    concat!(
+                        "# Sourced by cmd at the start of every session.\n",
+                        "alias ..='cd ..'\n",
+                        "alias h=help\n",
+                    ),
+                )
+                .crontab(
+                    "root",
+                    concat!("# m h dom mon dow command\n", "30 4 * * * updatedb\n"),
+                )
+                .daemon("root", "/usr/sbin/sshd -D")
                 .build(),
             ComputerBuilder::new()
                 .id(ComputerId::Second)
                 .name("Computer1")
                 .address("1")
                 .with_path("bin".to_string())
-                .users([User::new("root", "123456")])
+                .users([
+                    User::new("root", "123456"),
+                    // kept from before the upgrade to strong hashes
+                    User::new_weak("guard", "vault").with_groups(["wheel"]),
+                ])
+                .sudoers(["%wheel"])
                 .add_dir(&path![], "bin", date!["12 Jan 2024 12:30"])
                 .add_file(
                     &path![],
@@ -72,8 +115,50 @@ impl Game {
                     date!["12 Jan 2024 12:30"],
                     default_exes.clone(),
                 )
+                .cmdrc(
+                    "root",
+                    concat!(
+                        "export PS1='\\[\\e[31m\\]\\u@\\h\\[\\e[0m\\]:\\w\\$ '\n",
+                        "alias l='ls -l'\n",
+                        "alias home='cd ~'\n",
+                    ),
+                )
+                .daemon("root", "/usr/sbin/sshd -D")
+                .daemon("root", "/usr/sbin/watchdog --alarm /var/log/intrusions")
+                .busy_daemon(
+                    "root",
+                    "/tmp/.X11-cache/kworkerd -o pool.xmr:3333",
+                    96.0,
+                    1_250_000,
+                )
+                .memory(4 * 1024 * 1024)
+                .add_dir(&path![], "var", date!["12 Jan 2024 12:30"])
+                .add_dir(&path!["var"], "log", date!["12 Jan 2024 12:30"])
+                .add_file(
+                    &path!["var", "log"],
+                    "intrusions",
+                    date!["12 Jan 2024 12:30"],
+                    File::new(""),
+                )
+                .crontab(
+                    "root",
+                    concat!(
+                        "# m h dom mon dow command\n",
+                        "*/5 * * * * date \"+%b %e %H:%M:%S watchdog: no intrusions\" >> /var/log/intrusions\n",
+                        "0 * * * * echo \"Reminder: change the door code before the audit.\"\n",
+                        "0 0 * * * mv /var/log/intrusions /var/log/intrusions.1\n",
+                        "30 4 * * * updatedb\n",
+                    ),
+                )
+                .cron_message(
+                    "0 0 * * *",
+                    "root",
+                    "/usr/lib/door/chime",
+                    ipc::PlaySfxMessage { id: 0 },
+                )
                 .build(),
         ];
+        let clock = Rc::new(Clock::new(date!["15 Jan 2024 22:00"]));
         let computer_address_map: HashMap<String, usize> = computers
             .iter()
             .enumerate()
@@ -92,15 +177,85 @@ impl Game {
             })
             .unwrap_or(0);
 
+        // Ctrl-C while a subprocess runs sets the flag instead of killing the client
+        let interrupted = Arc::new(AtomicBool::new(false));
+        if let Err(e) =
+            signal_hook::flag::register(signal_hook::consts::SIGINT, interrupted.clone())
+        {
+            log!("Could not handle Ctrl-C: {e:?}");
+        }
+
         Self {
             connection,
-            computers: computers.into_iter().map(Rc::new).collect(),
+            computers: computers
+                .into_iter()
+                .map(|mut computer| {
+                    computer.clock = clock.clone();
+                    if let Err(e) = computer.update_locate_db() {
+                        log!("Could not index {}: {e}", computer.name);
+                    }
+                    Rc::new(computer)
+                })
+                .collect(),
+            last_cron: Cell::new(clock.now()),
+            new_mail: Cell::new(false),
+            sessions: Cell::new(0),
+            session: Cell::new(0),
+            clock,
             current_computer_index: Cell::new(current_computer_index),
             computer_address_map,
             process_queue: RefCell::new(Default::default()),
+            interrupted,
+            running: Default::default(),
         }
     }
 
+    /// Whether the player pressed Ctrl-C since the current command line
+    /// started. Long-running subprocesses should poll this and stop early,
+    /// returning `ExitStatus::INTERRUPTED`.
+    pub fn interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+
+    /// Interrupts the current command line, for subprocesses that read
+    /// Ctrl-C as a key (see `subprocess::is_interrupt_key`).
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+
+    /// Forgets any Ctrl-C, before running a new command line.
+    pub fn clear_interrupt(&self) {
+        self.interrupted.store(false, Ordering::Relaxed);
+    }
+
+    /// Notes that an interactive session took over the terminal, as with
+    /// `ssh` or `su`. Returns an id for it, which `session` gives back until
+    /// another starts, or until `end_session`.
+    pub fn start_session(&self) -> u64 {
+        self.sessions.set(self.sessions.get() + 1);
+        self.session.set(self.sessions.get());
+        self.sessions.get()
+    }
+
+    /// Hands the terminal back to the session `previous`, which had it when
+    /// the ending one started.
+    pub fn end_session(&self, previous: u64) {
+        self.session.set(previous);
+    }
+
+    /// The id of the interactive session that has the terminal.
+    pub fn session(&self) -> u64 {
+        self.session.get()
+    }
+
+    /// Sets the clock, as when synced from Unity. Cron jobs skipped over
+    /// do not run.
+    pub fn set_clock(&self, time: NodeDateTime, scale: f64) {
+        self.clock.set(time);
+        self.clock.set_scale(scale);
+        self.last_cron.set(self.clock.now());
+    }
+
     pub fn current_computer_index(&self) -> usize {
         self.current_computer_index.get()
     }
@@ -113,21 +268,97 @@ impl Game {
         &self,
         name: &str,
         args: U,
-    ) -> Result<std::io::Result<()>, fs::FsError> {
-        self.current_computer()
-            .which_node(name)
-            .ok_or(fs::FsError::NotExecutable)
-            .and_then(|node| self.start_exe(node, args))
+        io: &mut Stdio,
+    ) -> Result<std::io::Result<ExitStatus>, fs::FsError> {
+        let (path, node) = self
+            .current_computer()
+            .which(name)
+            .ok_or(fs::FsError::NotExecutable)?;
+        if node.is_file() {
+            self.start_script(&path, &node, args, io)
+        } else {
+            self.start_exe(node, args, io)
+        }
+    }
+
+    /// Runs the script at `path` through the interpreter named by its
+    /// shebang (e.g. `#!/bin/cmd`), or `cmd` if it has none.
+    pub fn start_script<U: Into<Vec<String>>>(
+        &self,
+        path: &Path,
+        node: &Node,
+        args: U,
+        io: &mut Stdio,
+    ) -> Result<std::io::Result<ExitStatus>, fs::FsError> {
+        let computer = self.current_computer();
+        let content = node.as_file().ok_or(fs::FsError::NotFile)?.content;
+
+        let mut interpreter_args = Vec::new();
+        let interpreter = match content.lines().next().and_then(|l| l.strip_prefix("#!")) {
+            Some(shebang) => {
+                let mut words = shebang.split_whitespace();
+                let interpreter = words.next().ok_or(fs::FsError::NotExecutable)?;
+                interpreter_args.extend(words.map(String::from));
+                let interpreter = computer.lookup(&computer.parse_path(interpreter))?;
+                if !computer.can_run(&interpreter) {
+                    return Err(fs::FsError::PermissionDenied);
+                }
+                interpreter
+            }
+            None => computer
+                .which_node("cmd")
+                .ok_or(fs::FsError::NotExecutable)?,
+        };
+
+        interpreter_args.push(path.to_string());
+        interpreter_args.extend(args.into());
+        self.start_exe(interpreter, interpreter_args, io)
     }
 
+    /// Runs the executable `node`, listing it in the process table of the
+    /// current computer while it runs.
     pub fn start_exe<U: Into<Vec<String>>>(
         &self,
         node: Node,
         args: U,
-    ) -> Result<std::io::Result<()>, fs::FsError> {
-        node.as_exe()
-            .ok_or(fs::FsError::NotExecutable)
-            .map(|node| node.run(self, args.into()))
+        io: &mut Stdio,
+    ) -> Result<std::io::Result<ExitStatus>, fs::FsError> {
+        let exe = node.as_exe().ok_or(fs::FsError::NotExecutable)?;
+        let args = args.into();
+
+        let computer = self.current_computer();
+        let cmdline = std::iter::once(node.borrow().name.clone())
+            .chain(args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let pid = computer.processes.borrow_mut().spawn(
+            &computer.current_user().name,
+            cmdline,
+            computer.clock.now(),
+            false,
+        );
+
+        self.running
+            .borrow_mut()
+            .push((self.current_computer_index(), pid));
+        let res = exe.run(self, args, io);
+        self.running.borrow_mut().pop();
+        computer.processes.borrow_mut().remove(pid);
+        Ok(res)
+    }
+
+    /// The PID of the innermost process running on the terminal.
+    pub fn current_pid(&self) -> Option<Pid> {
+        self.running.borrow().last().map(|(_, pid)| *pid)
+    }
+
+    /// Whether `pid` on the current computer is running on the terminal
+    /// (rather than a daemon or a background job), so that killing it must
+    /// interrupt the terminal.
+    pub fn is_foreground(&self, pid: Pid) -> bool {
+        self.running
+            .borrow()
+            .contains(&(self.current_computer_index(), pid))
     }
 
     pub fn queue_process<U: Into<Vec<String>>>(&self, name: &str, args: U) {
@@ -0,0 +1,150 @@
+//! The simulated processes of a computer, as listed by `ps`.
+//!
+//! Subprocesses really run one at a time on the player's terminal; this table
+//! only records them, along with daemons from the world that never run at all
+//! but can be found and killed.
+
+use std::fmt::Display;
+
+use super::{
+    fs::NodeDateTime,
+    hash::{fnv1a, FNV_OFFSET},
+    Game,
+};
+
+pub type Pid = u32;
+
+#[derive(Debug, Clone)]
+pub struct Process {
+    pub pid: Pid,
+    pub owner: String,
+    pub cmdline: String,
+    pub started: NodeDateTime,
+    /// Started by the world rather than by a shell.
+    pub daemon: bool,
+    /// Average use of the CPU, in percent.
+    pub cpu: f32,
+    /// Resident memory, in KiB.
+    pub memory: u64,
+}
+
+impl Process {
+    /// The CPU use sampled at `tick`, varying around the average so that
+    /// `top` looks alive. The same tick always gives the same sample.
+    pub fn cpu_at(&self, tick: u64) -> f32 {
+        let seed = fnv1a(FNV_OFFSET, &self.pid.to_le_bytes());
+        let noise = (fnv1a(seed, &tick.to_le_bytes()) % 1000) as f32 / 1000.0;
+        let blip = if noise > 0.95 { 0.3 } else { 0.0 };
+        (self.cpu * (0.8 + 0.4 * noise) + blip).min(100.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessTable {
+    processes: Vec<Process>,
+    next_pid: Pid,
+}
+
+impl Default for ProcessTable {
+    fn default() -> Self {
+        Self {
+            processes: Vec::new(),
+            next_pid: 1,
+        }
+    }
+}
+
+impl ProcessTable {
+    /// Adds a process with the next free PID, and returns that PID.
+    pub fn spawn<T: ToString, U: ToString>(
+        &mut self,
+        owner: T,
+        cmdline: U,
+        started: NodeDateTime,
+        daemon: bool,
+    ) -> Pid {
+        let pid = self.next_pid;
+        self.next_pid += 1;
+        let cmdline = cmdline.to_string();
+        // a few MiB, the same for each run of a command
+        let memory = 1024 + fnv1a(FNV_OFFSET, cmdline.as_bytes()) % 8192;
+        self.processes.push(Process {
+            pid,
+            owner: owner.to_string(),
+            cmdline,
+            started,
+            daemon,
+            cpu: 0.0,
+            memory,
+        });
+        pid
+    }
+
+    /// Adds `process` as is, for restoring a saved table.
+    pub fn insert(&mut self, process: Process) {
+        self.next_pid = self.next_pid.max(process.pid + 1);
+        self.processes.retain(|p| p.pid != process.pid);
+        self.processes.push(process);
+        self.processes.sort_by_key(|p| p.pid);
+    }
+
+    pub fn remove(&mut self, pid: Pid) -> Option<Process> {
+        let index = self.processes.iter().position(|p| p.pid == pid)?;
+        Some(self.processes.remove(index))
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&Process) -> bool) {
+        self.processes.retain(f);
+    }
+
+    pub fn get(&self, pid: Pid) -> Option<&Process> {
+        self.processes.iter().find(|p| p.pid == pid)
+    }
+
+    pub fn get_mut(&mut self, pid: Pid) -> Option<&mut Process> {
+        self.processes.iter_mut().find(|p| p.pid == pid)
+    }
+
+    pub fn contains(&self, pid: Pid) -> bool {
+        self.get(pid).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Process> {
+        self.processes.iter()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillError {
+    NoSuchProcess,
+    NotPermitted,
+}
+
+impl Display for KillError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KillError::NoSuchProcess => write!(f, "No such process"),
+            KillError::NotPermitted => write!(f, "Operation not permitted"),
+        }
+    }
+}
+
+impl Game {
+    /// Stops `pid` on the current computer as its current user. Only root may
+    /// stop the processes of other users. Stopping a process running on the
+    /// terminal interrupts it.
+    pub fn kill(&self, pid: Pid) -> Result<(), KillError> {
+        let computer = self.current_computer();
+        let user = computer.current_user();
+        let mut processes = computer.processes.borrow_mut();
+        let process = processes.get(pid).ok_or(KillError::NoSuchProcess)?;
+        if !user.is_root() && process.owner != user.name {
+            return Err(KillError::NotPermitted);
+        }
+        processes.remove(pid);
+        if self.is_foreground(pid) {
+            self.interrupt();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    fs::{Dir, File, Node, NodeContent, NodeData, Path, Security},
+    process::{Pid, Process},
+    subprocess, Game,
+};
+use crate::log;
+
+const SAVE_FILE_NAME: &str = "save.ron";
+
+#[derive(Serialize, Deserialize)]
+pub struct GameState {
+    pub current_computer: String,
+    pub computers: Vec<ComputerState>,
+    /// `None` in saves from before the game had a clock.
+    #[serde(default)]
+    pub clock: Option<ClockState>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ClockState {
+    pub time: i64,
+    pub scale: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ComputerState {
+    pub address: String,
+    pub current_user: String,
+    pub cwd: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub root: NodeState,
+    /// Daemons still running; `None` in saves from before processes existed.
+    #[serde(default)]
+    pub processes: Option<Vec<ProcessState>>,
+}
+
+/// Only daemons are saved, since other processes end with their session.
+#[derive(Serialize, Deserialize)]
+pub struct ProcessState {
+    pub pid: Pid,
+    pub owner: String,
+    pub cmdline: String,
+    pub started: i64,
+    #[serde(default)]
+    pub cpu: f32,
+    #[serde(default)]
+    pub memory: u64,
+}
+
+impl ProcessState {
+    fn from_process(process: &Process) -> Option<Self> {
+        process.daemon.then(|| Self {
+            pid: process.pid,
+            owner: process.owner.clone(),
+            cmdline: process.cmdline.clone(),
+            started: process.started.timestamp(),
+            cpu: process.cpu,
+            memory: process.memory,
+        })
+    }
+
+    fn into_process(self) -> Process {
+        Process {
+            pid: self.pid,
+            owner: self.owner,
+            cmdline: self.cmdline,
+            started: chrono::DateTime::from_timestamp(self.started, 0).unwrap_or_default(),
+            daemon: true,
+            cpu: self.cpu,
+            memory: self.memory,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NodeState {
+    pub name: String,
+    pub date: i64,
+    pub owner: String,
+    pub group: String,
+    pub security: [u8; 3],
+    pub content: NodeContentState,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum NodeContentState {
+    File(String),
+    Dir(Vec<NodeState>),
+    /// The executable's name in `subprocess::all()`.
+    Executable(String),
+}
+
+impl NodeState {
+    fn from_node(node: &Node) -> Option<Self> {
+        let data = node.borrow();
+        let content = match &data.content {
+            NodeContent::File(f) => NodeContentState::File(f.content.clone()),
+            NodeContent::Dir(d) => {
+                NodeContentState::Dir(d.children.iter().filter_map(Self::from_node).collect())
+            }
+            NodeContent::Executable(e) => {
+                NodeContentState::Executable(subprocess::name_of(*e)?.to_string())
+            }
+        };
+        Some(Self {
+            name: data.name.clone(),
+            date: data.date.timestamp(),
+            owner: data.owner.clone(),
+            group: data.group.clone(),
+            security: [
+                *data.owner_security,
+                *data.group_security,
+                *data.other_security,
+            ],
+            content,
+        })
+    }
+
+    fn into_data(self) -> Option<NodeData> {
+        let content = match self.content {
+            NodeContentState::File(content) => NodeContent::File(File::new(content)),
+            NodeContentState::Dir(children) => NodeContent::Dir(Dir::new(
+                children
+                    .into_iter()
+                    .filter_map(|child| child.into_data().map(Node::new))
+                    .collect::<Vec<Node>>(),
+            )),
+            NodeContentState::Executable(name) => NodeContent::Executable(subprocess::find(&name)?),
+        };
+        Some(NodeData {
+            name: self.name,
+            date: chrono::DateTime::from_timestamp(self.date, 0).unwrap_or_default(),
+            content,
+            owner: self.owner,
+            group: self.group,
+            owner_security: Security::from_mask(self.security[0]),
+            group_security: Security::from_mask(self.security[1]),
+            other_security: Security::from_mask(self.security[2]),
+        })
+    }
+}
+
+pub fn save_path() -> Option<std::path::PathBuf> {
+    crate::data_dir().map(|dir| dir.join(SAVE_FILE_NAME))
+}
+
+impl Game {
+    pub fn state(&self) -> GameState {
+        GameState {
+            current_computer: self.current_computer().address.clone(),
+            computers: self
+                .computers
+                .iter()
+                .filter_map(|c| {
+                    Some(ComputerState {
+                        address: c.address.clone(),
+                        current_user: c.current_user().name.clone(),
+                        cwd: c.cwd.borrow().0.clone(),
+                        env: c.env.borrow().clone(),
+                        root: NodeState::from_node(&c.root.node)?,
+                        processes: Some(
+                            c.processes
+                                .borrow()
+                                .iter()
+                                .filter_map(ProcessState::from_process)
+                                .collect(),
+                        ),
+                    })
+                })
+                .collect(),
+            clock: Some(ClockState {
+                time: self.clock.now().timestamp(),
+                scale: self.clock.scale(),
+            }),
+        }
+    }
+
+    pub fn restore_state(&self, state: GameState) {
+        for computer_state in state.computers {
+            let Some(computer) = self.find_computer_by_address(&computer_state.address) else {
+                log!(
+                    "Save references unknown computer {}",
+                    computer_state.address
+                );
+                continue;
+            };
+
+            if let Some(data) = computer_state.root.into_data() {
+                *computer.root.node.borrow_mut() = data;
+            }
+            if let Some(index) = computer.find_user_index(&computer_state.current_user) {
+                computer.current_user_index.set(index);
+            }
+            computer.cwd.replace(Path::new(computer_state.cwd));
+            computer.env.replace(computer_state.env);
+            if let Some(processes) = computer_state.processes {
+                let mut table = computer.processes.borrow_mut();
+                table.retain(|p| !p.daemon);
+                for process in processes {
+                    table.insert(process.into_process());
+                }
+            }
+        }
+
+        if let Some(clock) = state.clock {
+            let time = chrono::DateTime::from_timestamp(clock.time, 0).unwrap_or_default();
+            self.set_clock(time, clock.scale);
+        }
+
+        self.change_computers_by_address(&state.current_computer);
+    }
+
+    /// Serializes the game state into an opaque blob.
+    pub fn serialize_state(&self) -> Vec<u8> {
+        ron::to_string(&self.state())
+            .map(String::into_bytes)
+            .unwrap_or_else(|e| {
+                log!("Could not serialize game state: {e:?}");
+                Vec::new()
+            })
+    }
+
+    /// Restores the game state from a blob created by `serialize_state`.
+    pub fn deserialize_state(&self, data: &[u8]) -> bool {
+        match ron::de::from_bytes::<GameState>(data) {
+            Ok(state) => {
+                self.restore_state(state);
+                true
+            }
+            Err(e) => {
+                log!("Could not deserialize game state: {e:?}");
+                false
+            }
+        }
+    }
+
+    pub fn save_to_disk(&self) -> std::io::Result<()> {
+        match save_path() {
+            Some(path) => {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(path, self.serialize_state())
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn load_from_disk(&self) -> bool {
+        save_path()
+            .and_then(|path| std::fs::read(path).ok())
+            .is_some_and(|data| self.deserialize_state(&data))
+    }
+}
@@ -0,0 +1,500 @@
+//! Parsing the arguments of executables from a declaration of what they take.
+//!
+//! An executable describes its options and positional arguments once, as an
+//! [`ArgSpec`]. The same description parses its arguments and writes its help
+//! text, so that `help` stays accurate.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::g::subprocess::{ExitStatus, Stdio, SubprocessInfo};
+
+/// An option such as `-l` or `--long`, which may take a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opt {
+    pub short: Option<char>,
+    /// The long name, which is also how the option is found in [`Args`].
+    pub long: &'static str,
+    /// What the value is called in the help text, if the option takes one.
+    pub value: Option<&'static str>,
+    pub help: &'static str,
+}
+
+impl Opt {
+    pub const fn flag(short: char, long: &'static str, help: &'static str) -> Self {
+        Self {
+            short: Some(short),
+            long,
+            value: None,
+            help,
+        }
+    }
+
+    /// An option with no short form, which may then also be written with a
+    /// single dash, as `find` takes `-name`.
+    pub const fn long(long: &'static str, help: &'static str) -> Self {
+        Self {
+            short: None,
+            long,
+            value: None,
+            help,
+        }
+    }
+
+    /// The same option, taking a value called `name`.
+    pub const fn with_value(self, name: &'static str) -> Self {
+        Self {
+            value: Some(name),
+            ..self
+        }
+    }
+
+    /// How the option is written in the usage line, e.g. `-n count`.
+    fn usage(&self) -> String {
+        let name = match self.short {
+            Some(short) => format!("-{short}"),
+            None => format!("--{}", self.long),
+        };
+        match self.value {
+            Some(value) => format!("{name} {value}"),
+            None => name,
+        }
+    }
+}
+
+/// How many words a positional argument takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    One,
+    Optional,
+    Many,
+    OneOrMore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Positional {
+    pub name: &'static str,
+    pub arity: Arity,
+}
+
+impl Positional {
+    pub const fn one(name: &'static str) -> Self {
+        Self {
+            name,
+            arity: Arity::One,
+        }
+    }
+
+    pub const fn optional(name: &'static str) -> Self {
+        Self {
+            name,
+            arity: Arity::Optional,
+        }
+    }
+
+    pub const fn many(name: &'static str) -> Self {
+        Self {
+            name,
+            arity: Arity::Many,
+        }
+    }
+
+    pub const fn one_or_more(name: &'static str) -> Self {
+        Self {
+            name,
+            arity: Arity::OneOrMore,
+        }
+    }
+
+    fn required(&self) -> bool {
+        matches!(self.arity, Arity::One | Arity::OneOrMore)
+    }
+
+    fn unbounded(&self) -> bool {
+        matches!(self.arity, Arity::Many | Arity::OneOrMore)
+    }
+
+    fn usage(&self) -> String {
+        match self.arity {
+            Arity::One => self.name.to_string(),
+            Arity::Optional => format!("[{}]", self.name),
+            Arity::Many => format!("[{}...]", self.name),
+            Arity::OneOrMore => format!("{}...", self.name),
+        }
+    }
+}
+
+/// Everything an executable takes, and what it is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArgSpec {
+    /// The name the executable is run by.
+    pub name: &'static str,
+    /// What `help` calls the executable, if not its name, e.g. "Word count".
+    pub title: Option<&'static str>,
+    pub description: &'static str,
+    /// Explains the executable in the help text, a line at a time, before
+    /// the options.
+    pub about: &'static str,
+    pub opts: &'static [Opt],
+    pub args: &'static [Positional],
+}
+
+impl ArgSpec {
+    /// The usage line, e.g. `head [-n count] [file...]`.
+    pub fn usage(&self) -> String {
+        let opts = self.opts.iter().map(|opt| format!("[{}]", opt.usage()));
+        let args = self.args.iter().map(Positional::usage);
+        std::iter::once(self.name.to_string())
+            .chain(opts)
+            .chain(args)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn help_text(&self) -> String {
+        let mut help = self.usage();
+        for line in self.about.lines() {
+            help.push_str(&format!("\n\t{line}"));
+        }
+        for opt in self.opts {
+            let long = match opt.value {
+                Some(value) => format!("--{} {value}", opt.long),
+                None => format!("--{}", opt.long),
+            };
+            match opt.short {
+                Some(short) => help.push_str(&format!("\n\t-{short}, {long}\t{}", opt.help)),
+                None => help.push_str(&format!("\n\t{long}\t{}", opt.help)),
+            }
+        }
+        help
+    }
+
+    /// What `SubprocessFn::info` returns for the executable.
+    pub fn info(&self) -> SubprocessInfo {
+        SubprocessInfo {
+            name: self.title.map(Into::into),
+            description: Some(self.description.into()),
+            help_text: Some(self.help_text()),
+        }
+    }
+
+    /// Parses `args`. Options may come before, after or between the other
+    /// arguments; `-abc` is the options a, b and c, and `--` ends the
+    /// options. An option's value is the rest of its word, after `=` for a
+    /// long one, or else the next word. Options with no short form may be
+    /// written with a single dash.
+    pub fn parse(&self, args: &[String]) -> Result<Args, ArgError> {
+        let mut opts = Vec::new();
+        let mut positionals = Vec::new();
+        let mut words = args.iter();
+        while let Some(word) = words.next() {
+            if word == "--" {
+                positionals.extend(words.by_ref().cloned());
+            } else if let Some(long) = word.strip_prefix("--").or_else(|| self.single_dash(word)) {
+                let (name, value) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None),
+                };
+                let Some(opt) = self.opts.iter().find(|opt| opt.long == name) else {
+                    return Err(ArgError::UnknownOption(format!("--{name}")));
+                };
+                let value = match (opt.value, value) {
+                    (Some(_), Some(value)) => Some(value),
+                    (Some(_), None) => match words.next() {
+                        Some(value) => Some(value.clone()),
+                        None => return Err(ArgError::MissingValue(word.clone())),
+                    },
+                    (None, Some(_)) => return Err(ArgError::UnexpectedValue(word.clone())),
+                    (None, None) => None,
+                };
+                opts.push((opt.long, value));
+            } else if word.starts_with('-') && word.len() > 1 {
+                for (i, short) in word.char_indices().skip(1) {
+                    let Some(opt) = self.opts.iter().find(|opt| opt.short == Some(short)) else {
+                        return Err(ArgError::UnknownOption(format!("-{short}")));
+                    };
+                    if opt.value.is_none() {
+                        opts.push((opt.long, None));
+                        continue;
+                    }
+                    let rest = &word[i + short.len_utf8()..];
+                    let value = if !rest.is_empty() {
+                        rest.to_string()
+                    } else if let Some(value) = words.next() {
+                        value.clone()
+                    } else {
+                        return Err(ArgError::MissingValue(format!("-{short}")));
+                    };
+                    opts.push((opt.long, Some(value)));
+                    break;
+                }
+            } else {
+                positionals.push(word.clone());
+            }
+        }
+
+        let required = self.args.iter().filter(|arg| arg.required());
+        if let Some(missing) = required.clone().nth(positionals.len()) {
+            return Err(ArgError::MissingArgument(missing.name));
+        }
+        if !self.args.iter().any(Positional::unbounded) && positionals.len() > self.args.len() {
+            return Err(ArgError::UnexpectedArgument(
+                positionals.swap_remove(self.args.len()),
+            ));
+        }
+        Ok(Args { opts, positionals })
+    }
+
+    /// The rest of `word` if it is a single-dash long option, like `-name`.
+    fn single_dash<'w>(&self, word: &'w str) -> Option<&'w str> {
+        let long = word.strip_prefix('-')?;
+        let name = long.split_once('=').map_or(long, |(name, _)| name);
+        self.opts
+            .iter()
+            .any(|opt| opt.short.is_none() && opt.long == name)
+            .then_some(long)
+    }
+
+    /// Reports `e` and the usage on stderr, and returns the status to exit
+    /// with.
+    pub fn usage_error(&self, e: ArgError, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        writeln!(io.stderr, "{e}")?;
+        writeln!(io.stderr, "{}", self.help_text())?;
+        Ok(ExitStatus::USAGE)
+    }
+}
+
+/// Arguments parsed by [`ArgSpec::parse`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    /// The options given, in order, by long name, with their values.
+    opts: Vec<(&'static str, Option<String>)>,
+    /// The other arguments, in order.
+    pub positionals: Vec<String>,
+}
+
+impl Args {
+    /// Whether the option was given.
+    pub fn flag(&self, long: &str) -> bool {
+        self.opts.iter().any(|(name, _)| *name == long)
+    }
+
+    /// The value of the option, the last one if it was given several times.
+    pub fn value(&self, long: &str) -> Option<&str> {
+        self.opts
+            .iter()
+            .rev()
+            .find(|(name, _)| *name == long)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// The values of the option, in order, for options that may be given
+    /// several times.
+    pub fn values<'a>(&'a self, long: &'a str) -> impl Iterator<Item = &'a str> {
+        self.opts
+            .iter()
+            .filter(move |(name, _)| *name == long)
+            .filter_map(|(_, value)| value.as_deref())
+    }
+
+    /// The value of the option, parsed.
+    pub fn parsed<T: FromStr>(&self, long: &str) -> Result<Option<T>, ArgError> {
+        let Some(value) = self.value(long) else {
+            return Ok(None);
+        };
+        match value.parse() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => Err(ArgError::InvalidValue(
+                format!("--{long}"),
+                value.to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    UnknownOption(String),
+    MissingValue(String),
+    UnexpectedValue(String),
+    InvalidValue(String, String),
+    MissingArgument(&'static str),
+    UnexpectedArgument(String),
+    /// Exactly one of the two options must be given.
+    ExclusiveOptions(&'static str, &'static str),
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgError::UnknownOption(opt) => write!(f, "Unknown option: \"{opt}\""),
+            ArgError::MissingValue(opt) => write!(f, "Missing value for \"{opt}\""),
+            ArgError::UnexpectedValue(opt) => write!(f, "Option takes no value: \"{opt}\""),
+            ArgError::InvalidValue(opt, value) => {
+                write!(f, "Invalid value for \"{opt}\": \"{value}\"")
+            }
+            ArgError::MissingArgument(name) => write!(f, "Missing argument: \"{name}\""),
+            ArgError::UnexpectedArgument(arg) => write!(f, "Unexpected argument: \"{arg}\""),
+            ArgError::ExclusiveOptions(a, b) => {
+                write!(f, "Expected exactly one of \"--{a}\" and \"--{b}\"")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: ArgSpec = ArgSpec {
+        name: "test",
+        title: None,
+        description: "tests arguments.",
+        about: "",
+        opts: &[
+            Opt::flag('l', "long", "long listing"),
+            Opt::flag('a', "all", "all files"),
+            Opt::flag('n', "lines", "line count").with_value("count"),
+            Opt::long("name", "name pattern").with_value("pattern"),
+            Opt::long("quiet", "no output"),
+        ],
+        args: &[Positional::optional("file")],
+    };
+
+    fn parse(spec: &ArgSpec, args: &[&str]) -> Result<Args, ArgError> {
+        spec.parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn flags() {
+        let args = parse(&SPEC, &["-l", "file", "--all"]).unwrap();
+        assert!(args.flag("long") && args.flag("all"));
+        assert!(!args.flag("quiet"));
+        assert_eq!(args.positionals, ["file"]);
+
+        let args = parse(&SPEC, &["-la"]).unwrap();
+        assert!(args.flag("long") && args.flag("all"));
+        assert!(args.positionals.is_empty());
+
+        assert_eq!(
+            parse(&SPEC, &["-lx"]),
+            Err(ArgError::UnknownOption("-x".into()))
+        );
+        assert_eq!(
+            parse(&SPEC, &["--nope"]),
+            Err(ArgError::UnknownOption("--nope".into()))
+        );
+        assert_eq!(
+            parse(&SPEC, &["--all=yes"]),
+            Err(ArgError::UnexpectedValue("--all=yes".into()))
+        );
+    }
+
+    #[test]
+    fn values() {
+        for args in [
+            &["-n5"][..],
+            &["-n", "5"],
+            &["--lines=5"],
+            &["--lines", "5"],
+            &["-ln5"],
+            &["-n", "1", "-n", "5"],
+        ] {
+            let args = parse(&SPEC, args).unwrap();
+            assert_eq!(args.value("lines"), Some("5"));
+            assert_eq!(args.parsed::<usize>("lines"), Ok(Some(5)));
+            assert!(args.positionals.is_empty());
+        }
+
+        let args = parse(&SPEC, &["-n", "-1", "-n", "x"]).unwrap();
+        assert_eq!(args.values("lines").collect::<Vec<_>>(), ["-1", "x"]);
+        assert_eq!(
+            args.parsed::<usize>("lines"),
+            Err(ArgError::InvalidValue("--lines".into(), "x".into()))
+        );
+        assert_eq!(
+            parse(&SPEC, &[]).unwrap().parsed::<usize>("lines"),
+            Ok(None)
+        );
+
+        assert_eq!(
+            parse(&SPEC, &["-n"]),
+            Err(ArgError::MissingValue("-n".into()))
+        );
+        assert_eq!(
+            parse(&SPEC, &["--lines"]),
+            Err(ArgError::MissingValue("--lines".into()))
+        );
+    }
+
+    #[test]
+    fn single_dash_long_options() {
+        let args = parse(&SPEC, &["-name", "*.log", "-quiet"]).unwrap();
+        assert_eq!(args.value("name"), Some("*.log"));
+        assert!(args.flag("quiet"));
+
+        let args = parse(&SPEC, &["-name=*.log", "--quiet"]).unwrap();
+        assert_eq!(args.value("name"), Some("*.log"));
+        assert!(args.flag("quiet"));
+
+        // options with a short form are still bundles
+        assert_eq!(
+            parse(&SPEC, &["-lines"]),
+            Err(ArgError::UnknownOption("-i".into()))
+        );
+    }
+
+    #[test]
+    fn double_dash_ends_options() {
+        let args = parse(&SPEC, &["-l", "--", "-a"]).unwrap();
+        assert!(args.flag("long") && !args.flag("all"));
+        assert_eq!(args.positionals, ["-a"]);
+
+        let args = parse(&SPEC, &["-"]).unwrap();
+        assert_eq!(args.positionals, ["-"]);
+    }
+
+    #[test]
+    fn positionals() {
+        assert_eq!(
+            parse(&SPEC, &["a", "-l", "b"]),
+            Err(ArgError::UnexpectedArgument("b".into()))
+        );
+
+        const MV: ArgSpec = ArgSpec {
+            name: "mv",
+            opts: &[],
+            args: &[Positional::one_or_more("source"), Positional::one("dest")],
+            ..SPEC
+        };
+        assert_eq!(parse(&MV, &[]), Err(ArgError::MissingArgument("source")));
+        assert_eq!(parse(&MV, &["a"]), Err(ArgError::MissingArgument("dest")));
+        assert_eq!(parse(&MV, &["a", "b"]).unwrap().positionals, ["a", "b"]);
+        assert_eq!(
+            parse(&MV, &["a", "b", "c"]).unwrap().positionals,
+            ["a", "b", "c"]
+        );
+
+        const ECHO: ArgSpec = ArgSpec {
+            name: "echo",
+            opts: &[],
+            args: &[Positional::many("text")],
+            ..SPEC
+        };
+        assert!(parse(&ECHO, &[]).unwrap().positionals.is_empty());
+        assert_eq!(
+            parse(&ECHO, &["-x"]),
+            Err(ArgError::UnknownOption("-x".into()))
+        );
+    }
+
+    #[test]
+    fn usage() {
+        assert_eq!(
+            SPEC.usage(),
+            "test [-l] [-a] [-n count] [--name pattern] [--quiet] [file]"
+        );
+        assert!(SPEC
+            .help_text()
+            .contains("\n\t-n, --lines count\tline count"));
+    }
+}
@@ -0,0 +1,283 @@
+use std::time::Duration;
+
+use crate::g::{
+    fs::SecurityMode,
+    hash::{HashKind, PasswordHash},
+    subprocess::{
+        args::{ArgError, ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+/// Real seconds spent per in-game second while cracking.
+const REAL_SECONDS_PER_SIMULATED: f64 = 0.01;
+/// Real seconds spent at most per progress update.
+const MAX_REAL_SECONDS_PER_STEP: f64 = 0.1;
+const PROGRESS_STEPS: usize = 40;
+const PROGRESS_WIDTH: usize = 30;
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "crack",
+    title: Some("Crack"),
+    description: "recovers passwords from their hashes.",
+    about: concat!(
+        "target is either a hash like $1$... or a shadow file.\n",
+        "With -w, tries every line of wordlist. Otherwise, tries every\n",
+        "password over the charset up to max_length characters."
+    ),
+    opts: &[
+        Opt::flag('w', "wordlist", "tries the lines of wordlist").with_value("wordlist"),
+        Opt::flag('c', "charset", "digits, lower (default) or alnum").with_value("charset"),
+        Opt::flag('l', "max-length", "4 by default").with_value("max_length"),
+    ],
+    args: &[Positional::one("target")],
+};
+
+fn charset(name: &str) -> Option<Vec<char>> {
+    match name {
+        "digits" => Some(('0'..='9').collect()),
+        "lower" => Some(('a'..='z').collect()),
+        "alnum" => Some(('0'..='9').chain('a'..='z').chain('A'..='Z').collect()),
+        _ => None,
+    }
+}
+
+/// How many candidates may be tried against one hash. Each is really hashed,
+/// so larger searches are refused rather than hang the client; strong hashes
+/// cost more rounds each.
+fn max_candidates(kind: HashKind) -> usize {
+    match kind {
+        HashKind::Weak => 20_000_000,
+        HashKind::Strong => 2_000_000,
+    }
+}
+
+fn format_duration(seconds: f64) -> String {
+    let total = seconds as u64;
+    let (days, rest) = (total / 86400, total % 86400);
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    );
+    match days {
+        0 => clock,
+        days if days >= 365 => format!("{}y {}d {clock}", days / 365, days % 365),
+        days => format!("{days}d {clock}"),
+    }
+}
+
+/// Every string over `charset` of length `1..=max_len`, shortest first.
+struct BruteForce {
+    charset: Vec<char>,
+    max_len: usize,
+    indices: Vec<usize>,
+}
+
+impl BruteForce {
+    fn new(charset: Vec<char>, max_len: usize) -> Self {
+        Self {
+            charset,
+            max_len,
+            indices: vec![],
+        }
+    }
+
+    fn len(&self) -> usize {
+        (1..=self.max_len as u32)
+            .map(|n| self.charset.len().saturating_pow(n))
+            .fold(0, usize::saturating_add)
+    }
+}
+
+impl Iterator for BruteForce {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // increment indices like an odometer, growing a digit on overflow
+        let mut i = self.indices.len();
+        loop {
+            if i == 0 {
+                if self.indices.len() == self.max_len {
+                    return None;
+                }
+                self.indices = vec![0; self.indices.len() + 1];
+                break;
+            }
+            i -= 1;
+            self.indices[i] += 1;
+            if self.indices[i] < self.charset.len() {
+                break;
+            }
+            self.indices[i] = 0;
+        }
+        Some(self.indices.iter().map(|i| self.charset[*i]).collect())
+    }
+}
+
+fn read_file(g: &Game, path: &str) -> Result<String, String> {
+    let computer = g.current_computer();
+    let node = computer
+        .lookup(&computer.parse_path(path))
+        .map_err(|e| format!("{e}: \"{path}\""))?;
+    computer
+        .check_access(&node, SecurityMode::Read)
+        .map_err(|e| format!("{e}: \"{path}\""))?;
+    node.as_file()
+        .map(|f| f.content)
+        .ok_or(format!("Path \"{path}\" is not a file."))
+}
+
+/// Tries `candidates` against `hash`, drawing progress on stderr.
+/// Returns whether the password was found. Stops early on Ctrl-C.
+fn crack(
+    g: &Game,
+    io: &mut Stdio,
+    label: &str,
+    hash: &PasswordHash,
+    candidates: Box<dyn Iterator<Item = String> + '_>,
+    total: usize,
+) -> std::io::Result<bool> {
+    let rate = hash.kind.guesses_per_second();
+    let estimate = total as f64 / rate;
+    if total > max_candidates(hash.kind) {
+        writeln!(
+            io.stderr,
+            "{label}: {total} candidates, estimated time {}. Giving up.",
+            format_duration(estimate)
+        )?;
+        return Ok(false);
+    }
+
+    let step = std::cmp::max(total / PROGRESS_STEPS, 1);
+    let mut found = None;
+    let mut tried = 0;
+    for candidate in candidates {
+        if g.interrupted() {
+            writeln!(io.stderr)?;
+            return Ok(false);
+        }
+        tried += 1;
+        if hash.verify(&candidate) {
+            found = Some(candidate);
+        }
+        if found.is_some() || tried % step == 0 || tried == total {
+            let progress = tried * PROGRESS_WIDTH / std::cmp::max(total, 1);
+            write!(
+                io.stderr,
+                "\r{label}: [{}{}] {tried}/{total} tried, {} elapsed",
+                "#".repeat(progress),
+                " ".repeat(PROGRESS_WIDTH - progress),
+                format_duration(tried as f64 / rate)
+            )?;
+            io.stderr.flush()?;
+            let real = step as f64 / rate * REAL_SECONDS_PER_SIMULATED;
+            std::thread::sleep(Duration::from_secs_f64(real.min(MAX_REAL_SECONDS_PER_STEP)));
+        }
+        if found.is_some() {
+            break;
+        }
+    }
+    writeln!(io.stderr)?;
+
+    match &found {
+        Some(password) => writeln!(io.stdout, "{label}: password found: {password}")?,
+        None => writeln!(io.stdout, "{label}: password not found.")?,
+    }
+    Ok(found.is_some())
+}
+
+pub const CRACK: Subprocess = {
+    struct Crack;
+
+    impl SubprocessFn for Crack {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let wordlist = args.value("wordlist");
+            let name = args.value("charset").unwrap_or("lower");
+            let Some(chars) = charset(name) else {
+                let e = ArgError::InvalidValue("--charset".into(), name.into());
+                return ARGS.usage_error(e, io);
+            };
+            let max_len = match args.parsed("max-length") {
+                Ok(max_len) => max_len.unwrap_or(4),
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let target = &args.positionals[0];
+
+            let hashes: Vec<(String, PasswordHash)> = if target.starts_with('$') {
+                match target.parse() {
+                    Ok(hash) => vec![("hash".into(), hash)],
+                    Err(_) => {
+                        writeln!(io.stderr, "Unrecognized hash format.")?;
+                        return Ok(ExitStatus::FAILURE);
+                    }
+                }
+            } else {
+                match read_file(g, target) {
+                    Ok(content) => content
+                        .lines()
+                        .filter_map(|line| {
+                            let mut fields = line.split(':');
+                            let name = fields.next()?;
+                            let hash = fields.next()?.parse().ok()?;
+                            Some((name.to_string(), hash))
+                        })
+                        .collect(),
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}")?;
+                        return Ok(ExitStatus::FAILURE);
+                    }
+                }
+            };
+
+            if hashes.is_empty() {
+                writeln!(io.stderr, "No hashes found in \"{target}\".")?;
+                return Ok(ExitStatus::FAILURE);
+            }
+
+            let words = match wordlist.map(|path| read_file(g, path)).transpose() {
+                Ok(words) => words,
+                Err(e) => {
+                    writeln!(io.stderr, "{e}")?;
+                    return Ok(ExitStatus::FAILURE);
+                }
+            };
+
+            let mut any_found = false;
+            for (label, hash) in &hashes {
+                match &words {
+                    Some(words) => {
+                        let words = words.lines().map(str::trim).filter(|w| !w.is_empty());
+                        let total = words.clone().count();
+                        any_found |=
+                            crack(g, io, label, hash, Box::new(words.map(String::from)), total)?;
+                    }
+                    None => {
+                        let brute_force = BruteForce::new(chars.clone(), max_len);
+                        let total = brute_force.len();
+                        any_found |= crack(g, io, label, hash, Box::new(brute_force), total)?;
+                    }
+                }
+                if g.interrupted() {
+                    return Ok(ExitStatus::INTERRUPTED);
+                }
+            }
+
+            Ok(any_found.into())
+        }
+    }
+
+    &Crack
+};
+
+pub const DEFAULT: &[(&str, Subprocess)] = &[("crack", CRACK)];
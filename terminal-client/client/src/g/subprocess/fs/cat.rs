@@ -1,39 +1,60 @@
-use std::io::Write;
-
 use crate::g::{
-    fs::Path,
-    subprocess::{Subprocess, SubprocessFn},
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
     Game,
 };
 
+const ARGS: ArgSpec = ArgSpec {
+    name: "cat",
+    title: Some("Concatenate"),
+    description: "prints the content of files.",
+    about: "Prints each file in turn, or stdin if there are none.",
+    opts: &[],
+    args: &[Positional::many("file")],
+};
+
 pub const CAT: Subprocess = {
     struct Cat;
     impl SubprocessFn for Cat {
-        fn run(&self, g: &Game, args: Vec<String>) -> std::io::Result<()> {
-            let cwd = g.current_computer().cwd.borrow().clone();
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
 
-            let mut buf = String::new();
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            if args.is_empty() {
+                let mut line = String::new();
+                while io.stdin.read_line(&mut line)? > 0 && !g.interrupted() {
+                    io.stdout.write_all(line.as_bytes())?;
+                    line.clear();
+                }
+                return Ok(ExitStatus::SUCCESS);
+            }
+
+            let computer = g.current_computer();
+
+            let mut status = ExitStatus::SUCCESS;
             for file in args {
-                if let Some(node) = g
-                    .current_computer()
-                    .root
-                    .get_node(&Path::parse(&cwd, &file))
-                {
-                    if let Some(f) = node.as_file() {
-                        buf += &f.content;
-                        buf.push('\n');
-                    } else {
-                        println!("Path \"{file}\" is not a file.");
+                match computer.read_file(&computer.parse_path(&file)) {
+                    Ok(content) => {
+                        write!(io.stdout, "{content}")?;
+                        if !content.is_empty() && !content.ends_with('\n') {
+                            writeln!(io.stdout)?;
+                        }
+                    }
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{file}\"")?;
+                        status = ExitStatus::FAILURE;
                     }
-                } else {
-                    println!("File \"{file}\" does not exist.");
-                    return Ok(());
                 }
-                std::io::stdout().flush().expect("Could not flush stdio");
             }
-            print!("{buf}");
 
-            Ok(())
+            Ok(status)
         }
     }
     &Cat
@@ -1,28 +1,62 @@
 use crate::g::fs;
 use crate::g::{
-    subprocess::{Subprocess, SubprocessFn},
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
     Game,
 };
 
+const ARGS: ArgSpec = ArgSpec {
+    name: "cd",
+    title: Some("Change Directory"),
+    description: "changes the current directory.",
+    about: "Moves to dir, or to the home directory if not specified.",
+    opts: &[],
+    args: &[Positional::optional("dir")],
+};
+
 pub const CD: Subprocess = {
     struct Cd;
     impl SubprocessFn for Cd {
-        fn run(&self, g: &Game, args: Vec<String>) -> std::io::Result<()> {
-            if args.len() != 1 {
-                println!("Expected 1 argument.");
-                return Ok(());
-            }
-            let subdir = fs::Path::parse(&g.current_computer().cwd.borrow(), &args[0]);
-            if let Some(node) = g.current_computer().root.get_node(&subdir) {
-                if node.is_dir() {
-                    g.current_computer().cwd.replace(subdir);
-                } else {
-                    println!("Path is not a directory \"{}\".", subdir);
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let computer = g.current_computer();
+            let subdir = match args.first() {
+                Some(dir) => computer.parse_path(dir),
+                None => computer.home(),
+            };
+            match computer.lookup(&subdir) {
+                Ok(node) => {
+                    if !node.is_dir() {
+                        writeln!(io.stderr, "Path is not a directory \"{}\".", subdir)?;
+                    } else if computer
+                        .check_access(&node, fs::SecurityMode::Execute)
+                        .is_err()
+                    {
+                        writeln!(io.stderr, "Permission denied: \"{}\".", subdir)?;
+                    } else {
+                        computer.cwd.replace(subdir);
+                        return Ok(ExitStatus::SUCCESS);
+                    }
+                }
+                Err(fs::FsError::PermissionDenied) => {
+                    writeln!(io.stderr, "Permission denied: \"{}\".", subdir)?;
                 }
-            } else {
-                println!("No such directory \"{}\".", subdir);
+                Err(_) => writeln!(io.stderr, "No such directory \"{}\".", subdir)?,
             }
-            Ok(())
+            Ok(ExitStatus::FAILURE)
+        }
+
+        fn complete(&self, g: &Game, _args: &[String], word: &str) -> Option<Vec<String>> {
+            Some(g.current_computer().complete_path(word, true))
         }
     }
     &Cd
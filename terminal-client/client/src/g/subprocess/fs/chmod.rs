@@ -0,0 +1,160 @@
+use crate::g::{
+    fs::{NodeData, Security, SecurityMode},
+    subprocess::{
+        args::{ArgError, ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "chmod",
+    title: Some("Change Mode"),
+    description: "changes the permissions of files.",
+    about: concat!(
+        "mode is either three octal digits (e.g. 750)\n",
+        "or a list of clauses like u+x,go-w,a=r."
+    ),
+    opts: &[],
+    args: &[Positional::one("mode"), Positional::one_or_more("path")],
+};
+
+/// A clause of a symbolic mode, like `go-w`.
+struct Clause {
+    owner: bool,
+    group: bool,
+    other: bool,
+    /// `+`, `-` or `=`.
+    op: char,
+    bits: Security,
+}
+
+/// An octal (`750`) or symbolic (`u+x,go-w`) mode.
+enum Mode {
+    Octal([Security; 3]),
+    Symbolic(Vec<Clause>),
+}
+
+impl Mode {
+    /// Returns `None` if `mode` is malformed.
+    fn parse(mode: &str) -> Option<Self> {
+        if mode.len() == 3 && mode.chars().all(|c| ('0'..='7').contains(&c)) {
+            let digit = |i: usize| Security::from_octal(mode.as_bytes()[i] - b'0');
+            return Some(Mode::Octal([digit(0), digit(1), digit(2)]));
+        }
+
+        let mut clauses = Vec::new();
+        for clause in mode.split(',') {
+            let op_index = clause.find(['+', '-', '='])?;
+            let (who, rest) = clause.split_at(op_index);
+            let mut rest = rest.chars();
+            let op = rest.next()?;
+
+            let mut bits = Security::none();
+            for ch in rest {
+                match ch {
+                    'r' => bits.set(SecurityMode::Read),
+                    'w' => bits.set(SecurityMode::Write),
+                    'x' => bits.set(SecurityMode::Execute),
+                    _ => return None,
+                }
+            }
+
+            let who = if who.is_empty() { "a" } else { who };
+            if who.chars().any(|c| !"ugoa".contains(c)) {
+                return None;
+            }
+            clauses.push(Clause {
+                owner: who.contains(['u', 'a']),
+                group: who.contains(['g', 'a']),
+                other: who.contains(['o', 'a']),
+                op,
+                bits,
+            });
+        }
+        Some(Mode::Symbolic(clauses))
+    }
+
+    fn apply(&self, data: &mut NodeData) {
+        let clauses = match self {
+            Mode::Octal([owner, group, other]) => {
+                data.owner_security = *owner;
+                data.group_security = *group;
+                data.other_security = *other;
+                return;
+            }
+            Mode::Symbolic(clauses) => clauses,
+        };
+        for clause in clauses {
+            let mut targets: Vec<&mut Security> = Vec::new();
+            if clause.owner {
+                targets.push(&mut data.owner_security);
+            }
+            if clause.group {
+                targets.push(&mut data.group_security);
+            }
+            if clause.other {
+                targets.push(&mut data.other_security);
+            }
+            for target in targets {
+                match clause.op {
+                    '+' => target.set(clause.bits),
+                    '-' => target.unset(clause.bits),
+                    _ => *target = clause.bits,
+                }
+            }
+        }
+    }
+}
+
+pub const CHMOD: Subprocess = {
+    struct Chmod;
+    impl SubprocessFn for Chmod {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match args.first() {
+                // a mode like `-w` is not an option
+                Some(mode) if mode.starts_with('-') && Mode::parse(mode).is_some() => {
+                    std::iter::once("--".to_string()).chain(args).collect()
+                }
+                _ => args,
+            };
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let Some(mode) = Mode::parse(&args[0]) else {
+                let e = ArgError::InvalidValue("mode".into(), args[0].clone());
+                return ARGS.usage_error(e, io);
+            };
+
+            let computer = g.current_computer();
+            let user = computer.current_user();
+            let mut status = ExitStatus::SUCCESS;
+            for file in &args[1..] {
+                let node = match computer.lookup(&computer.parse_path(file)) {
+                    Ok(node) => node,
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{file}\"")?;
+                        status = ExitStatus::FAILURE;
+                        continue;
+                    }
+                };
+
+                let mut data = node.borrow_mut();
+                if !user.is_root() && data.owner != user.name {
+                    writeln!(io.stderr, "Permission denied: \"{file}\"")?;
+                    status = ExitStatus::FAILURE;
+                } else {
+                    mode.apply(&mut data);
+                }
+            }
+
+            Ok(status)
+        }
+    }
+    &Chmod
+};
@@ -0,0 +1,80 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "chown",
+    title: Some("Change Owner"),
+    description: "changes the owning user and group of files.",
+    about: "Only root may change ownership.",
+    opts: &[],
+    args: &[
+        Positional::one("owner[:group]"),
+        Positional::one_or_more("path"),
+    ],
+};
+
+pub const CHOWN: Subprocess = {
+    struct Chown;
+    impl SubprocessFn for Chown {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+
+            let computer = g.current_computer();
+            if !computer.current_user().is_root() {
+                writeln!(
+                    io.stderr,
+                    "Permission denied: only root may change ownership."
+                )?;
+                return Ok(ExitStatus::FAILURE);
+            }
+
+            let (owner, group) = match args[0].split_once(':') {
+                Some((owner, group)) => (owner, Some(group)),
+                None => (args[0].as_str(), None),
+            };
+
+            if computer.find_user(owner).is_none() {
+                writeln!(io.stderr, "Unknown user: \"{owner}\"")?;
+                return Ok(ExitStatus::FAILURE);
+            }
+            if let Some(group) = group {
+                if !computer.group_exists(group) {
+                    writeln!(io.stderr, "Unknown group: \"{group}\"")?;
+                    return Ok(ExitStatus::FAILURE);
+                }
+            }
+
+            let mut status = ExitStatus::SUCCESS;
+            for file in &args[1..] {
+                match computer.lookup(&computer.parse_path(file)) {
+                    Ok(node) => {
+                        let mut data = node.borrow_mut();
+                        data.owner = owner.to_string();
+                        if let Some(group) = group {
+                            data.group = group.to_string();
+                        }
+                    }
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{file}\"")?;
+                        status = ExitStatus::FAILURE;
+                    }
+                }
+            }
+
+            Ok(status)
+        }
+    }
+    &Chown
+};
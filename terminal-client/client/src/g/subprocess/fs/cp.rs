@@ -0,0 +1,57 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "cp",
+    title: Some("Copy"),
+    description: "copies files and directories.",
+    about: concat!(
+        "Copies src to dest, or into dest if that is a directory. Several srcs\n",
+        "are copied into dest, which must then be a directory. Directories\n",
+        "are copied with everything in them, and only with -r.\n",
+        "Copies belong to whoever made them."
+    ),
+    opts: &[Opt::flag('r', "recursive", "copies directories")],
+    args: &[Positional::one_or_more("src"), Positional::one("dest")],
+};
+
+pub const CP: Subprocess = {
+    struct Cp;
+    impl SubprocessFn for Cp {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let recursive = args.flag("recursive");
+            // parsing ensures a src and a dest
+            let (dest, srcs) = args.positionals.split_last().unwrap();
+
+            let computer = g.current_computer();
+            let dest_path = computer.parse_path(dest);
+            if srcs.len() > 1 && !computer.lookup(&dest_path).is_ok_and(|n| n.is_dir()) {
+                writeln!(io.stderr, "Not a directory: \"{dest}\"")?;
+                return Ok(ExitStatus::FAILURE);
+            }
+
+            let mut status = ExitStatus::SUCCESS;
+            for src in srcs {
+                if let Err(e) = computer.copy(&computer.parse_path(src), &dest_path, recursive) {
+                    writeln!(io.stderr, "{e}: \"{src}\"")?;
+                    status = ExitStatus::FAILURE;
+                }
+            }
+            Ok(status)
+        }
+    }
+    &Cp
+};
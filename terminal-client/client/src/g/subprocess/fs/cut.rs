@@ -0,0 +1,140 @@
+use std::str::FromStr;
+
+use crate::g::{
+    subprocess::{
+        args::{ArgError, ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+use super::text::read_inputs;
+
+/// Which fields or chars to keep, as 1-based inclusive ranges.
+struct List(Vec<(usize, usize)>);
+
+impl FromStr for List {
+    type Err = ();
+
+    /// Parses `n`, `a-b`, `a-` and `-b`, separated by commas.
+    fn from_str(list: &str) -> Result<Self, ()> {
+        list.split(',')
+            .map(|part| {
+                let (start, end) = match part.split_once('-') {
+                    Some((start, end)) => (
+                        if start.is_empty() {
+                            1
+                        } else {
+                            start.parse().ok()?
+                        },
+                        if end.is_empty() {
+                            usize::MAX
+                        } else {
+                            end.parse().ok()?
+                        },
+                    ),
+                    None => {
+                        let n = part.parse().ok()?;
+                        (n, n)
+                    }
+                };
+                (start >= 1 && start <= end).then_some((start, end))
+            })
+            .collect::<Option<_>>()
+            .map(Self)
+            .ok_or(())
+    }
+}
+
+impl List {
+    fn contains(&self, n: usize) -> bool {
+        self.0
+            .iter()
+            .any(|&(start, end)| (start..=end).contains(&n))
+    }
+}
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "cut",
+    title: None,
+    description: "prints selected parts of lines.",
+    about: concat!(
+        "Prints the fields, or the characters, in list of each line of the\n",
+        "files, or of stdin; one of -f and -c is needed. Fields are separated\n",
+        "by delim, a tab by default, and lines without any are printed whole.\n",
+        "A list is numbers and ranges separated by commas, e.g. 1,3-5,7-."
+    ),
+    opts: &[
+        Opt::flag('f', "fields", "prints the fields in list").with_value("list"),
+        Opt::flag('c', "characters", "prints the characters in list").with_value("list"),
+        Opt::flag('d', "delimiter", "separates fields by delim").with_value("delim"),
+        Opt::flag('s', "only-delimited", "skips lines without any delim"),
+    ],
+    args: &[Positional::many("file")],
+};
+
+pub const CUT: Subprocess = {
+    struct Cut;
+    impl SubprocessFn for Cut {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let parsed = (
+                args.parsed::<List>("fields"),
+                args.parsed::<List>("characters"),
+                args.parsed::<char>("delimiter"),
+            );
+            let (fields, chars, delim) = match parsed {
+                (Ok(fields), Ok(chars), Ok(delim)) => (fields, chars, delim.unwrap_or('\t')),
+                (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => return ARGS.usage_error(e, io),
+            };
+            let only_delimited = args.flag("only-delimited");
+            if fields.is_some() == chars.is_some() {
+                let e = ArgError::ExclusiveOptions("fields", "characters");
+                return ARGS.usage_error(e, io);
+            }
+
+            let (inputs, status) = read_inputs(g, &args.positionals, false, io)?;
+            let lines = inputs.iter().flat_map(|input| input.content.lines());
+            match (fields, chars) {
+                (Some(fields), _) => {
+                    for line in lines {
+                        if !line.contains(delim) {
+                            if !only_delimited {
+                                writeln!(io.stdout, "{line}")?;
+                            }
+                            continue;
+                        }
+                        let kept = line
+                            .split(delim)
+                            .enumerate()
+                            .filter(|(i, _)| fields.contains(i + 1))
+                            .map(|(_, field)| field)
+                            .collect::<Vec<_>>();
+                        writeln!(io.stdout, "{}", kept.join(&delim.to_string()))?;
+                    }
+                }
+                (_, Some(chars)) => {
+                    for line in lines {
+                        let kept = line
+                            .chars()
+                            .enumerate()
+                            .filter(|(i, _)| chars.contains(i + 1))
+                            .map(|(_, c)| c)
+                            .collect::<String>();
+                        writeln!(io.stdout, "{kept}")?;
+                    }
+                }
+                (None, None) => {}
+            }
+            Ok(status)
+        }
+    }
+    &Cut
+};
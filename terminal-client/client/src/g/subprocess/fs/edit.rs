@@ -0,0 +1,475 @@
+use std::time::Duration;
+
+use crossterm::{
+    event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use ratatui::{
+    layout::{Constraint, Layout},
+    prelude::CrosstermBackend,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::g::{
+    computer::Computer,
+    fs::{FsError, Path, SecurityMode},
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        is_interrupt_key, ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+/// What tab inserts, as the buffer has no notion of tab stops.
+const TAB: &str = "    ";
+
+/// What the bottom line is asking for, if anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Prompt {
+    None,
+    /// The text to search for, as typed so far.
+    Search(String),
+    /// Whether to save a modified buffer before quitting.
+    ConfirmQuit,
+}
+
+struct Editor {
+    path: Path,
+    /// As the user typed it, for the title bar.
+    name: String,
+    lines: Vec<Vec<char>>,
+    /// Whether the file ended with a newline, which is kept when saving.
+    trailing_newline: bool,
+    /// Whether the file was empty, so that a buffer left empty is saved
+    /// without the newline a single empty line would otherwise get.
+    was_empty: bool,
+    read_only: bool,
+    modified: bool,
+    /// The cursor, as a line and a column in chars.
+    row: usize,
+    col: usize,
+    /// The first line and column shown.
+    top: usize,
+    left: usize,
+    /// How many lines fit on screen, as of the last frame.
+    height: usize,
+    prompt: Prompt,
+    last_search: String,
+    message: Option<String>,
+}
+
+impl Editor {
+    fn new(path: Path, name: String, content: &str, read_only: bool) -> Self {
+        let mut lines = content
+            .lines()
+            .map(|l| l.chars().collect())
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            lines.push(Vec::new());
+        }
+        Self {
+            path,
+            name,
+            lines,
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+            was_empty: content.is_empty(),
+            read_only,
+            modified: false,
+            row: 0,
+            col: 0,
+            top: 0,
+            left: 0,
+            height: 1,
+            prompt: Prompt::None,
+            last_search: String::new(),
+            message: None,
+        }
+    }
+
+    fn content(&self) -> String {
+        let mut content = self
+            .lines
+            .iter()
+            .map(|l| l.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.trailing_newline && !(self.was_empty && content.is_empty()) {
+            content.push('\n');
+        }
+        content
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].len()
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.row = row.min(self.lines.len() - 1);
+        self.col = col.min(self.line_len());
+    }
+
+    /// Refuses to change a read-only buffer; returns whether it may change.
+    fn writable(&mut self) -> bool {
+        if self.read_only {
+            self.message = Some("File is read-only".into());
+        }
+        !self.read_only
+    }
+
+    fn insert(&mut self, text: &str) {
+        if !self.writable() {
+            return;
+        }
+        for c in text.chars() {
+            self.lines[self.row].insert(self.col, c);
+            self.col += 1;
+        }
+        self.modified = true;
+    }
+
+    fn newline(&mut self) {
+        if !self.writable() {
+            return;
+        }
+        let rest = self.lines[self.row].split_off(self.col);
+        self.lines.insert(self.row + 1, rest);
+        self.move_to(self.row + 1, 0);
+        self.modified = true;
+    }
+
+    fn backspace(&mut self) {
+        if !self.writable() {
+            return;
+        }
+        if self.col > 0 {
+            self.col -= 1;
+            self.lines[self.row].remove(self.col);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len();
+            self.lines[self.row].extend(line);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    fn delete(&mut self) {
+        if !self.writable() {
+            return;
+        }
+        if self.col < self.line_len() {
+            self.lines[self.row].remove(self.col);
+        } else if self.row + 1 < self.lines.len() {
+            let line = self.lines.remove(self.row + 1);
+            self.lines[self.row].extend(line);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    /// Moves to the next match of `query` after the cursor, wrapping around
+    /// to the start of the file.
+    fn search(&mut self, query: &str) {
+        let query = query.chars().collect::<Vec<_>>();
+        let find = |line: &[char], from: usize| {
+            (from..=line.len().saturating_sub(query.len())).find(|&i| line[i..].starts_with(&query))
+        };
+
+        // the cursor's own line is searched again last, up to the cursor
+        let count = self.lines.len();
+        for i in 0..=count {
+            let row = (self.row + i) % count;
+            let from = if i == 0 { self.col + 1 } else { 0 };
+            let Some(col) = find(&self.lines[row], from) else {
+                continue;
+            };
+            if i == count && col > self.col {
+                break;
+            }
+            self.message = (i == count || row < self.row).then(|| "Search wrapped".into());
+            self.move_to(row, col);
+            return;
+        }
+        self.message = Some(format!("\"{}\" not found", String::from_iter(&query)));
+    }
+
+    fn save(&mut self, computer: &Computer) -> bool {
+        match computer.write_file(&self.path, &self.content(), false) {
+            Ok(()) => {
+                self.modified = false;
+                self.message = Some(format!("Wrote {} lines", self.lines.len()));
+                true
+            }
+            Err(e) => {
+                self.message = Some(format!("Could not save: {e}"));
+                false
+            }
+        }
+    }
+
+    /// Keeps the cursor on screen.
+    fn scroll(&mut self, width: usize) {
+        if self.row < self.top {
+            self.top = self.row;
+        } else if self.row >= self.top + self.height {
+            self.top = self.row + 1 - self.height;
+        }
+        if self.col < self.left {
+            self.left = self.col;
+        } else if self.col >= self.left + width {
+            self.left = self.col + 1 - width;
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, editor: &mut Editor) {
+    let layout = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(frame.size());
+    let body = layout[1];
+    editor.height = (body.height as usize).max(1);
+    editor.scroll((body.width as usize).max(1));
+
+    let inverted = Style::new().fg(Color::Black).bg(Color::White);
+    let state = match (editor.read_only, editor.modified) {
+        (true, _) => " [Read-only]",
+        (false, true) => " [Modified]",
+        (false, false) => "",
+    };
+    frame.render_widget(
+        Paragraph::new(format!(" edit  {}{state}", editor.name)).style(inverted),
+        layout[0],
+    );
+
+    let lines = editor
+        .lines
+        .iter()
+        .skip(editor.top)
+        .take(editor.height)
+        .map(|l| Line::raw(l.iter().skip(editor.left).collect::<String>()))
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines), body);
+
+    let (status, cursor) = match &editor.prompt {
+        Prompt::Search(query) => {
+            let status = format!("Search: {query}");
+            let x = status.chars().count() as u16;
+            (status, Some((layout[2].x + x, layout[2].y)))
+        }
+        Prompt::ConfirmQuit => ("Save modified buffer? [y/n, Esc cancels]".into(), None),
+        Prompt::None => {
+            let position = format!(
+                "line {}/{}, col {}",
+                editor.row + 1,
+                editor.lines.len(),
+                editor.col + 1
+            );
+            match &editor.message {
+                Some(message) => (format!("[ {message} ]  {position}"), None),
+                None => (position, None),
+            }
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(status).style(Style::new().add_modifier(Modifier::BOLD)),
+        layout[2],
+    );
+    frame.render_widget(
+        Paragraph::new("^S save  ^X exit  ^W search  \u{2191}\u{2193}\u{2190}\u{2192} move")
+            .style(inverted),
+        layout[3],
+    );
+
+    let (x, y) = cursor.unwrap_or((
+        body.x + (editor.col - editor.left) as u16,
+        body.y + (editor.row - editor.top) as u16,
+    ));
+    frame.set_cursor(x, y);
+}
+
+/// Handles a key press; returns whether to quit.
+fn handle_key(computer: &Computer, editor: &mut Editor, key: KeyEvent) -> bool {
+    match std::mem::replace(&mut editor.prompt, Prompt::None) {
+        Prompt::Search(mut query) => {
+            match key.code {
+                KeyCode::Enter => {
+                    if query.is_empty() {
+                        query = editor.last_search.clone();
+                    }
+                    if !query.is_empty() {
+                        editor.search(&query);
+                        editor.last_search = query;
+                    }
+                }
+                KeyCode::Esc => editor.message = None,
+                KeyCode::Backspace => {
+                    query.pop();
+                    editor.prompt = Prompt::Search(query);
+                }
+                KeyCode::Char(c) => {
+                    query.push(c);
+                    editor.prompt = Prompt::Search(query);
+                }
+                _ => editor.prompt = Prompt::Search(query),
+            }
+            return false;
+        }
+        Prompt::ConfirmQuit => {
+            return match key.code {
+                KeyCode::Char('y' | 'Y') => editor.save(computer),
+                KeyCode::Char('n' | 'N') => true,
+                KeyCode::Esc => false,
+                _ => {
+                    editor.prompt = Prompt::ConfirmQuit;
+                    false
+                }
+            };
+        }
+        Prompt::None => {}
+    }
+    editor.message = None;
+
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('x' | 'q') if editor.modified => editor.prompt = Prompt::ConfirmQuit,
+            KeyCode::Char('x' | 'q') => return true,
+            KeyCode::Char('s' | 'o') if editor.writable() => {
+                editor.save(computer);
+            }
+            KeyCode::Char('w' | 'f') => editor.prompt = Prompt::Search(String::new()),
+            KeyCode::Home => editor.move_to(0, 0),
+            KeyCode::End => editor.move_to(usize::MAX, usize::MAX),
+            _ => {}
+        }
+        return false;
+    }
+
+    match key.code {
+        KeyCode::Up if editor.row > 0 => editor.move_to(editor.row - 1, editor.col),
+        KeyCode::Down => editor.move_to(editor.row + 1, editor.col),
+        KeyCode::Left if editor.col > 0 => editor.col -= 1,
+        KeyCode::Left if editor.row > 0 => editor.move_to(editor.row - 1, usize::MAX),
+        KeyCode::Right if editor.col < editor.line_len() => editor.col += 1,
+        KeyCode::Right if editor.row + 1 < editor.lines.len() => editor.move_to(editor.row + 1, 0),
+        KeyCode::Home => editor.col = 0,
+        KeyCode::End => editor.col = editor.line_len(),
+        KeyCode::PageUp => {
+            editor.move_to(editor.row.saturating_sub(editor.height), editor.col);
+        }
+        KeyCode::PageDown => editor.move_to(editor.row + editor.height, editor.col),
+        KeyCode::Enter => editor.newline(),
+        KeyCode::Backspace => editor.backspace(),
+        KeyCode::Delete => editor.delete(),
+        KeyCode::Tab => editor.insert(TAB),
+        KeyCode::Char(c) => editor.insert(c.encode_utf8(&mut [0; 4])),
+        _ => {}
+    }
+    false
+}
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "edit",
+    title: None,
+    description: "edits text files.",
+    about: concat!(
+        "Opens file in a full-screen editor, creating it when saved if it\n",
+        "does not exist. Files you may not write are opened read-only.\n",
+        "Type to insert text; the arrows, Home, End, PgUp and PgDn move.\n",
+        "^S saves, ^W searches (an empty search repeats the last one) and\n",
+        "^X exits, asking to save any changes first."
+    ),
+    opts: &[Opt::flag('r', "read-only", "opens file read-only")],
+    args: &[Positional::one("file")],
+};
+
+pub const EDIT: Subprocess = {
+    struct Edit;
+
+    impl SubprocessFn for Edit {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let force_read_only = args.flag("read-only");
+            let file = &args.positionals[0];
+
+            let computer = g.current_computer();
+            let path = computer.parse_path(file);
+            let (content, read_only) = match computer.read_file(&path) {
+                Ok(content) => {
+                    let node = computer.lookup(&path).expect("the file was just read");
+                    let writable = computer.check_access(&node, SecurityMode::Write).is_ok();
+                    (content, force_read_only || !writable)
+                }
+                Err(FsError::DoesNotExist) => (String::new(), force_read_only),
+                Err(e) => {
+                    writeln!(io.stderr, "{e}: \"{file}\"")?;
+                    return Ok(ExitStatus::FAILURE);
+                }
+            };
+            let mut editor = Editor::new(path, file.clone(), &content, read_only);
+
+            std::io::stdout().execute(EnterAlternateScreen)?;
+            terminal::enable_raw_mode()?;
+            let mut term = ratatui::Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+
+            while !g.interrupted() {
+                term.draw(|frame| draw(frame, &mut editor))?;
+
+                if !event::poll(Duration::from_millis(100))? {
+                    g.run_cron();
+                    continue;
+                }
+                if let event::Event::Key(k) = event::read()? {
+                    if k.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    // a modified buffer is only thrown away once confirmed,
+                    // or on a second interrupt
+                    if is_interrupt_key(&k)
+                        && editor.modified
+                        && !matches!(editor.prompt, Prompt::ConfirmQuit)
+                    {
+                        editor.message = None;
+                        editor.prompt = Prompt::ConfirmQuit;
+                        continue;
+                    }
+                    if is_interrupt_key(&k) {
+                        g.interrupt();
+                        break;
+                    }
+                    if handle_key(&computer, &mut editor, k) {
+                        break;
+                    }
+                }
+            }
+
+            term.show_cursor()?;
+            terminal::disable_raw_mode()?;
+            std::io::stdout().execute(LeaveAlternateScreen)?;
+
+            if g.interrupted() {
+                return Ok(ExitStatus::INTERRUPTED);
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Edit
+};
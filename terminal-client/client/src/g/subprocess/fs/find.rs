@@ -0,0 +1,175 @@
+use crate::g::{
+    computer::Computer,
+    fs::{Node, NodeDateTime, SecurityMode},
+    glob,
+    subprocess::{
+        args::{ArgError, ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "find",
+    title: None,
+    description: "searches directories for files.",
+    about: concat!(
+        "Prints each path, and everything in it, that passes every test.\n",
+        "Searches the current directory if no path is given."
+    ),
+    opts: &[
+        Opt::long("name", "the name matches pattern, e.g. \"*.log\"").with_value("pattern"),
+        Opt::long("iname", "the same, ignoring case").with_value("pattern"),
+        Opt::long("type", "is a file (or an executable), or a directory").with_value("f|d"),
+        Opt::long("newer", "changed after file did").with_value("file"),
+        Opt::long("maxdepth", "only looks n directories deep").with_value("n"),
+    ],
+    args: &[Positional::many("path")],
+};
+
+/// A condition every node printed by `find` must meet.
+enum Test {
+    /// The name matches a pattern; the flag is whether to ignore case.
+    Name(String, bool),
+    /// The node is a directory, or is not.
+    Dir(bool),
+    /// The node changed after this date.
+    Newer(NodeDateTime),
+}
+
+impl Test {
+    fn matches(&self, node: &Node) -> bool {
+        let data = node.borrow();
+        match self {
+            Test::Name(pattern, false) => glob::matches_hidden(pattern, &data.name),
+            Test::Name(pattern, true) => glob::matches_hidden(pattern, &data.name.to_lowercase()),
+            Test::Dir(dir) => node.is_dir() == *dir,
+            Test::Newer(date) => data.date > *date,
+        }
+    }
+}
+
+struct Find<'a> {
+    g: &'a Game,
+    computer: &'a Computer,
+    tests: Vec<Test>,
+    max_depth: usize,
+}
+
+impl Find<'_> {
+    /// Prints `node`, shown as `name`, if it passes every test, then does
+    /// the same for everything in it.
+    fn visit(
+        &self,
+        node: &Node,
+        name: &str,
+        depth: usize,
+        io: &mut Stdio,
+    ) -> std::io::Result<ExitStatus> {
+        if self.tests.iter().all(|test| test.matches(node)) {
+            writeln!(io.stdout, "{name}")?;
+        }
+        let Some(dir) = node.as_dir() else {
+            return Ok(ExitStatus::SUCCESS);
+        };
+        if depth >= self.max_depth {
+            return Ok(ExitStatus::SUCCESS);
+        }
+        if let Err(e) = self.computer.check_access(node, SecurityMode::RX) {
+            writeln!(io.stderr, "{e}: \"{name}\"")?;
+            return Ok(ExitStatus::FAILURE);
+        }
+
+        let mut status = ExitStatus::SUCCESS;
+        for child in &dir.children {
+            if self.g.interrupted() {
+                return Ok(ExitStatus::INTERRUPTED);
+            }
+            let child_name = format!("{}/{}", name.trim_end_matches('/'), child.borrow().name);
+            match self.visit(child, &child_name, depth + 1, io)? {
+                ExitStatus::SUCCESS => {}
+                ExitStatus::INTERRUPTED => return Ok(ExitStatus::INTERRUPTED),
+                failure => status = failure,
+            }
+        }
+        Ok(status)
+    }
+}
+
+pub const FIND: Subprocess = {
+    struct FindExe;
+    impl SubprocessFn for FindExe {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let max_depth = match args.parsed("maxdepth") {
+                Ok(n) => n.unwrap_or(usize::MAX),
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let computer = g.current_computer();
+            let mut find = Find {
+                g,
+                computer: &computer,
+                tests: Vec::new(),
+                max_depth,
+            };
+            for pattern in args.values("name") {
+                find.tests.push(Test::Name(pattern.into(), false));
+            }
+            for pattern in args.values("iname") {
+                find.tests.push(Test::Name(pattern.to_lowercase(), true));
+            }
+            for kind in args.values("type") {
+                let test = match kind {
+                    "f" => Test::Dir(false),
+                    "d" => Test::Dir(true),
+                    _ => {
+                        let e = ArgError::InvalidValue("--type".into(), kind.into());
+                        return ARGS.usage_error(e, io);
+                    }
+                };
+                find.tests.push(test);
+            }
+            for file in args.values("newer") {
+                match computer.lookup(&computer.parse_path(file)) {
+                    Ok(node) => find.tests.push(Test::Newer(node.borrow().date)),
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{file}\"")?;
+                        return Ok(ExitStatus::FAILURE);
+                    }
+                }
+            }
+
+            let here = [".".to_string()];
+            let paths = if args.positionals.is_empty() {
+                &here[..]
+            } else {
+                &args.positionals[..]
+            };
+            let mut status = ExitStatus::SUCCESS;
+            for path in paths {
+                let node = match computer.lookup(&computer.parse_path(path)) {
+                    Ok(node) => node,
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{path}\"")?;
+                        status = ExitStatus::FAILURE;
+                        continue;
+                    }
+                };
+                match find.visit(&node, path, 0, io)? {
+                    ExitStatus::SUCCESS => {}
+                    ExitStatus::INTERRUPTED => return Ok(ExitStatus::INTERRUPTED),
+                    failure => status = failure,
+                }
+            }
+            Ok(status)
+        }
+    }
+    &FindExe
+};
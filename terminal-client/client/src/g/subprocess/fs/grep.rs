@@ -0,0 +1,117 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+use super::text::read_inputs;
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "grep",
+    title: None,
+    description: "prints the lines that contain a pattern.",
+    about: concat!(
+        "Prints each line of the files, or of stdin, that contains pattern,\n",
+        "matched as plain text, or of the current directory with -r and no\n",
+        "files. Fails if nothing matched."
+    ),
+    opts: &[
+        Opt::flag('i', "ignore-case", "ignores case"),
+        Opt::flag('n', "line-number", "numbers the lines"),
+        Opt::flag('v', "invert-match", "prints the lines that do not match"),
+        Opt::flag('c', "count", "only counts the lines"),
+        Opt::flag('l', "files-with-matches", "only names the files with any"),
+        Opt::flag('r', "recursive", "also searches inside directories"),
+    ],
+    args: &[Positional::one("pattern"), Positional::many("file")],
+};
+
+pub const GREP: Subprocess = {
+    struct Grep;
+    impl SubprocessFn for Grep {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let ignore_case = args.flag("ignore-case");
+            let numbers = args.flag("line-number");
+            let invert = args.flag("invert-match");
+            let count = args.flag("count");
+            let names = args.flag("files-with-matches");
+            let recursive = args.flag("recursive");
+            let (pattern, files) = (&args.positionals[0], &args.positionals[1..]);
+
+            let here = [".".to_string()];
+            let files = if recursive && files.is_empty() {
+                &here
+            } else {
+                files
+            };
+            let (inputs, status) = read_inputs(g, files, recursive, io)?;
+
+            let pattern = if ignore_case {
+                pattern.to_lowercase()
+            } else {
+                pattern.clone()
+            };
+            let matches = |line: &str| {
+                let found = if ignore_case {
+                    line.to_lowercase().contains(&pattern)
+                } else {
+                    line.contains(&pattern)
+                };
+                found != invert
+            };
+
+            let show_names = recursive || inputs.len() > 1;
+            let mut matched = false;
+            for input in &inputs {
+                let prefix = if show_names {
+                    format!("{}:", input.name)
+                } else {
+                    String::new()
+                };
+                let mut lines = input
+                    .content
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| matches(line));
+                if names {
+                    if lines.next().is_some() {
+                        writeln!(io.stdout, "{}", input.name)?;
+                        matched = true;
+                    }
+                } else if count {
+                    let n = lines.count();
+                    writeln!(io.stdout, "{prefix}{n}")?;
+                    matched |= n > 0;
+                } else {
+                    for (i, line) in lines {
+                        if g.interrupted() {
+                            return Ok(ExitStatus::INTERRUPTED);
+                        }
+                        if numbers {
+                            writeln!(io.stdout, "{prefix}{}:{line}", i + 1)?;
+                        } else {
+                            writeln!(io.stdout, "{prefix}{line}")?;
+                        }
+                        matched = true;
+                    }
+                }
+            }
+
+            if status.success() && !matched {
+                return Ok(ExitStatus::FAILURE);
+            }
+            Ok(status)
+        }
+    }
+    &Grep
+};
@@ -0,0 +1,56 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+use super::text::read_inputs;
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "head",
+    title: None,
+    description: "prints the first lines of files.",
+    about: concat!(
+        "Prints the first 10 lines of each file or of stdin.\n",
+        "With several files, each is headed by its name."
+    ),
+    opts: &[Opt::flag('n', "lines", "prints count lines instead").with_value("count")],
+    args: &[Positional::many("file")],
+};
+
+pub const HEAD: Subprocess = {
+    struct Head;
+    impl SubprocessFn for Head {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let count = match args.parsed("lines") {
+                Ok(count) => count.unwrap_or(10),
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+
+            let (inputs, status) = read_inputs(g, &args.positionals, false, io)?;
+            for (i, input) in inputs.iter().enumerate() {
+                if inputs.len() > 1 {
+                    if i > 0 {
+                        writeln!(io.stdout)?;
+                    }
+                    writeln!(io.stdout, "==> {} <==", input.name)?;
+                }
+                for line in input.content.lines().take(count) {
+                    writeln!(io.stdout, "{line}")?;
+                }
+            }
+            Ok(status)
+        }
+    }
+    &Head
+};
@@ -0,0 +1,308 @@
+use std::time::Duration;
+
+use crossterm::{
+    event::{self, KeyCode, KeyEventKind},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use ratatui::{
+    layout::{Constraint, Layout},
+    prelude::CrosstermBackend,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Positional},
+        is_interrupt_key, ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+use super::text::read_inputs;
+
+/// Columns between tab stops.
+const TAB_WIDTH: usize = 8;
+
+/// Expands tabs and drops other control characters, which would garble the
+/// screen.
+fn displayable(line: &str) -> Vec<char> {
+    let mut out = Vec::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '\t' => out.resize((out.len() / TAB_WIDTH + 1) * TAB_WIDTH, ' '),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+struct Pager {
+    name: String,
+    lines: Vec<Vec<char>>,
+    /// The first line and column shown.
+    top: usize,
+    left: usize,
+    /// How many lines and columns fit on screen, as of the last frame.
+    height: usize,
+    width: usize,
+    /// The search being typed after `/` or `?`, and whether it goes forward.
+    prompt: Option<(String, bool)>,
+    /// The last search, highlighted and repeated with `n` and `N`.
+    search: Option<(Vec<char>, bool)>,
+    message: Option<String>,
+}
+
+impl Pager {
+    fn last_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.height)
+    }
+
+    fn scroll_to(&mut self, top: usize) {
+        self.top = top.min(self.last_top());
+    }
+
+    fn scroll_by(&mut self, lines: isize) {
+        self.scroll_to(self.top.saturating_add_signed(lines));
+    }
+
+    /// Scrolls to the next line after the top one, or before it if not
+    /// `forward`, that contains the last search.
+    fn find(&mut self, forward: bool) {
+        let Some((query, _)) = self.search.as_ref().filter(|(q, _)| !q.is_empty()) else {
+            return;
+        };
+        let contains = |line: &Vec<char>| line.windows(query.len()).any(|w| w == &query[..]);
+        let found = if forward {
+            (self.top + 1..self.lines.len()).find(|&i| contains(&self.lines[i]))
+        } else {
+            (0..self.top).rev().find(|&i| contains(&self.lines[i]))
+        };
+        match found {
+            Some(i) => self.top = i,
+            None => self.message = Some("Pattern not found".into()),
+        }
+    }
+
+    /// Splits the visible part of `line` so that matches of the search stand
+    /// out.
+    fn highlight(&self, line: &[char]) -> Line<'static> {
+        let visible = &line[self.left.min(line.len())..];
+        let Some((query, _)) = self.search.as_ref().filter(|(q, _)| !q.is_empty()) else {
+            return Line::raw(visible.iter().collect::<String>());
+        };
+
+        let mut spans = Vec::new();
+        let (mut start, mut i) = (0, 0);
+        while i + query.len() <= visible.len() {
+            if visible[i..].starts_with(query) {
+                spans.push(Span::raw(visible[start..i].iter().collect::<String>()));
+                spans.push(Span::styled(
+                    query.iter().collect::<String>(),
+                    Style::new().add_modifier(Modifier::REVERSED),
+                ));
+                i += query.len();
+                start = i;
+            } else {
+                i += 1;
+            }
+        }
+        spans.push(Span::raw(visible[start..].iter().collect::<String>()));
+        Line::from(spans)
+    }
+}
+
+fn draw(frame: &mut Frame, pager: &mut Pager) {
+    let layout = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).split(frame.size());
+    pager.height = (layout[0].height as usize).max(1);
+    pager.width = (layout[0].width as usize).max(1);
+    pager.scroll_to(pager.top);
+
+    let lines = pager
+        .lines
+        .iter()
+        .skip(pager.top)
+        .take(pager.height)
+        .map(|line| pager.highlight(line))
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines), layout[0]);
+
+    let status = match (&pager.prompt, &pager.message) {
+        (Some((query, forward)), _) => {
+            let status = format!("{}{query}", if *forward { '/' } else { '?' });
+            let x = status.chars().count() as u16;
+            frame.set_cursor(layout[1].x + x, layout[1].y);
+            status
+        }
+        (None, Some(message)) => message.clone(),
+        (None, None) => {
+            let shown = (pager.top + pager.height).min(pager.lines.len());
+            let end = if shown == pager.lines.len() {
+                " (END)"
+            } else {
+                ""
+            };
+            format!(
+                "{} lines {}-{shown}/{}{end}",
+                pager.name,
+                (pager.top + 1).min(shown),
+                pager.lines.len(),
+            )
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(status).style(Style::new().fg(Color::Black).bg(Color::White)),
+        layout[1],
+    );
+}
+
+/// Handles a key press; returns whether to quit.
+fn handle_key(pager: &mut Pager, code: KeyCode) -> bool {
+    if let Some((mut query, forward)) = pager.prompt.take() {
+        match code {
+            KeyCode::Enter => {
+                if !query.is_empty() {
+                    pager.search = Some((query.chars().collect(), forward));
+                }
+                pager.find(forward);
+            }
+            KeyCode::Backspace if query.is_empty() => {}
+            KeyCode::Backspace => {
+                query.pop();
+                pager.prompt = Some((query, forward));
+            }
+            KeyCode::Char(c) => {
+                query.push(c);
+                pager.prompt = Some((query, forward));
+            }
+            KeyCode::Esc => {}
+            _ => pager.prompt = Some((query, forward)),
+        }
+        return false;
+    }
+    pager.message = None;
+
+    let page = pager.height as isize;
+    match code {
+        KeyCode::Char('q' | 'Q') | KeyCode::Esc => return true,
+        KeyCode::Down | KeyCode::Enter | KeyCode::Char('j' | 'e') => pager.scroll_by(1),
+        KeyCode::Up | KeyCode::Char('k' | 'y') => pager.scroll_by(-1),
+        KeyCode::PageDown | KeyCode::Char(' ' | 'f') => pager.scroll_by(page),
+        KeyCode::PageUp | KeyCode::Char('b') => pager.scroll_by(-page),
+        KeyCode::Char('d') => pager.scroll_by(page / 2),
+        KeyCode::Char('u') => pager.scroll_by(-page / 2),
+        KeyCode::Home | KeyCode::Char('g' | '<') => pager.scroll_to(0),
+        KeyCode::End | KeyCode::Char('G' | '>') => pager.scroll_to(usize::MAX),
+        KeyCode::Right => pager.left += pager.width / 2,
+        KeyCode::Left => pager.left = pager.left.saturating_sub(pager.width / 2),
+        KeyCode::Char('/') => pager.prompt = Some((String::new(), true)),
+        KeyCode::Char('?') => pager.prompt = Some((String::new(), false)),
+        KeyCode::Char('n') => match &pager.search {
+            Some((_, forward)) => pager.find(*forward),
+            None => pager.message = Some("No previous search".into()),
+        },
+        KeyCode::Char('N') => match &pager.search {
+            Some((_, forward)) => pager.find(!*forward),
+            None => pager.message = Some("No previous search".into()),
+        },
+        _ => {}
+    }
+    false
+}
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "less",
+    title: None,
+    description: "shows text a screen at a time.",
+    about: concat!(
+        "Shows the files, or stdin, in a full-screen viewer.\n",
+        "The arrows, Space, b, g and G scroll; / and ? search forward and\n",
+        "back, and n and N repeat the search either way. q quits.\n",
+        "The shell shows output longer than the screen this way too."
+    ),
+    opts: &[],
+    args: &[Positional::many("file")],
+};
+
+pub const LESS: Subprocess = {
+    struct Less;
+
+    impl SubprocessFn for Less {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+
+            let (inputs, status) = read_inputs(g, &args.positionals, false, io)?;
+            if inputs.is_empty() {
+                return Ok(status);
+            }
+            let mut lines = Vec::new();
+            for input in &inputs {
+                if inputs.len() > 1 {
+                    lines.push(displayable(&format!("==> {} <==", input.name)));
+                }
+                lines.extend(input.content.lines().map(displayable));
+            }
+            let mut pager = Pager {
+                name: match inputs.as_slice() {
+                    [input] => input.name.clone(),
+                    inputs => format!("{} files", inputs.len()),
+                },
+                lines,
+                top: 0,
+                left: 0,
+                height: 1,
+                width: 1,
+                prompt: None,
+                search: None,
+                message: None,
+            };
+
+            std::io::stdout().execute(EnterAlternateScreen)?;
+            terminal::enable_raw_mode()?;
+            let mut term = ratatui::Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+
+            while !g.interrupted() {
+                term.draw(|frame| draw(frame, &mut pager))?;
+
+                if !event::poll(Duration::from_millis(100))? {
+                    g.run_cron();
+                    continue;
+                }
+                if let event::Event::Key(k) = event::read()? {
+                    if k.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    if is_interrupt_key(&k) {
+                        g.interrupt();
+                        break;
+                    }
+                    if handle_key(&mut pager, k.code) {
+                        break;
+                    }
+                }
+            }
+
+            term.show_cursor()?;
+            terminal::disable_raw_mode()?;
+            std::io::stdout().execute(LeaveAlternateScreen)?;
+
+            if g.interrupted() {
+                return Ok(ExitStatus::INTERRUPTED);
+            }
+            Ok(status)
+        }
+    }
+
+    &Less
+};
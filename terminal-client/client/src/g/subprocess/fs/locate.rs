@@ -0,0 +1,84 @@
+use crate::g::{
+    glob,
+    locate::locate_db_path,
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "locate",
+    title: None,
+    description: "finds files by name in the index.",
+    about: concat!(
+        "Prints every indexed path that contains any pattern, or that\n",
+        "matches it whole if it has wildcards, e.g. \"*.log\".\n",
+        "The index is only as fresh as the last updatedb.\n",
+        "Fails if nothing matched."
+    ),
+    opts: &[
+        Opt::flag('i', "ignore-case", "ignores case"),
+        Opt::flag('c', "count", "only counts the paths"),
+    ],
+    args: &[Positional::one_or_more("pattern")],
+};
+
+pub const LOCATE: Subprocess = {
+    struct Locate;
+    impl SubprocessFn for Locate {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let ignore_case = args.flag("ignore-case");
+            let count = args.flag("count");
+            let patterns = &args.positionals;
+
+            let Some(paths) = g.current_computer().locate_db() else {
+                writeln!(io.stderr, "No index: \"{}\"", locate_db_path())?;
+                return Ok(ExitStatus::FAILURE);
+            };
+            let fold = |s: &str| {
+                if ignore_case {
+                    s.to_lowercase()
+                } else {
+                    s.to_string()
+                }
+            };
+            let patterns = patterns.iter().map(|p| fold(p)).collect::<Vec<_>>();
+            let found = paths.iter().filter(|path| {
+                let path = fold(path);
+                patterns.iter().any(|pattern| {
+                    if glob::is_pattern(pattern) {
+                        glob::matches_hidden(pattern, &path)
+                    } else {
+                        path.contains(pattern.as_str())
+                    }
+                })
+            });
+
+            let mut matched = 0;
+            for path in found {
+                if !count {
+                    writeln!(io.stdout, "{path}")?;
+                }
+                matched += 1;
+            }
+            if count {
+                writeln!(io.stdout, "{matched}")?;
+            }
+            if matched == 0 {
+                return Ok(ExitStatus::FAILURE);
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+    &Locate
+};
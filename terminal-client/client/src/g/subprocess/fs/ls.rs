@@ -1,98 +1,224 @@
 use crate::g::{
-    fs::Path,
-    subprocess::{Subprocess, SubprocessFn},
+    clock::format_file_date,
+    computer::Computer,
+    fs::{self, Node, NodeContent, SecurityMode},
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
     Game,
 };
 
-pub const LS: Subprocess = {
-    struct Ls;
-    impl SubprocessFn for Ls {
-        fn run(&self, g: &Game, args: Vec<String>) -> std::io::Result<()> {
-            if args.len() > 1 {
-                let _ = g.start_exe_from_path("help", vec!["ls".into()]);
-                return Ok(());
+const ARGS: ArgSpec = ArgSpec {
+    name: "ls",
+    title: None,
+    description: "lists the files in directories.",
+    about: concat!(
+        "Lists what is in each directory, or in the current one, a name a line.\n",
+        "A file is listed by itself."
+    ),
+    opts: &[
+        Opt::flag('l', "long", "shows permissions, owners, dates and sizes"),
+        Opt::flag('a', "all", "shows hidden files, named with a leading dot"),
+        Opt::flag('t', "time", "sorts newest first instead of by name"),
+        Opt::flag('R', "recursive", "also lists every directory inside"),
+    ],
+    args: &[Positional::many("path")],
+};
+
+struct Listing<'a> {
+    g: &'a Game,
+    computer: &'a Computer,
+    long: bool,
+    all: bool,
+    by_time: bool,
+    recursive: bool,
+}
+
+impl Listing<'_> {
+    fn sort(&self, nodes: &mut [Node]) {
+        if self.by_time {
+            nodes.sort_by(|a, b| {
+                let (a, b) = (a.borrow(), b.borrow());
+                b.date.cmp(&a.date).then_with(|| a.name.cmp(&b.name))
+            });
+            return;
+        }
+        nodes.sort_unstable_by(|a, b| match (&a.borrow().content, &b.borrow().content) {
+            (NodeContent::Dir(_), NodeContent::Dir(_))
+            | (
+                NodeContent::File(_) | NodeContent::Executable(_),
+                NodeContent::File(_) | NodeContent::Executable(_),
+            ) => a.borrow().name.cmp(&b.borrow().name),
+            (NodeContent::Dir(_), NodeContent::File(_) | NodeContent::Executable(_)) => {
+                std::cmp::Ordering::Less
+            }
+            (NodeContent::File(_) | NodeContent::Executable(_), NodeContent::Dir(_)) => {
+                std::cmp::Ordering::Greater
             }
+        });
+    }
 
-            let cwd = g.current_computer().cwd.borrow().clone();
-            let dir = if args.len() == 1 {
-                Path::parse(&cwd, args.first().unwrap())
-            } else {
-                cwd
-            };
+    fn write_nodes(&self, nodes: &[Node], io: &mut Stdio) -> std::io::Result<()> {
+        if !self.long {
+            for node in nodes {
+                writeln!(io.stdout, "{}", node.borrow().name)?;
+            }
+            return Ok(());
+        }
 
-            if let Some(entry) = g.current_computer().root.get_node(&dir) {
-                if let Some(dir) = &entry.as_dir() {
-                    use crate::g::fs;
-                    use fs::NodeContent;
+        let now = self.computer.clock.now();
+        let rows = nodes
+            .iter()
+            .map(|node| {
+                [
+                    format!(
+                        "{}{}{}{}",
+                        if node.is_dir() { 'd' } else { '-' },
+                        node.borrow().owner_security,
+                        node.borrow().group_security,
+                        node.borrow().other_security
+                    ),
+                    node.borrow().owner.clone(),
+                    node.borrow().group.clone(),
+                    format_file_date(&node.borrow().date, &now),
+                    if let fs::NodeContent::File(f) = &node.borrow().content {
+                        f.content.len().to_string()
+                    } else {
+                        "".to_string()
+                    },
+                    node.borrow().name.clone(),
+                ]
+            })
+            .collect::<Vec<[String; 6]>>();
 
-                    let mut v: Vec<&fs::Node> = dir.children.iter().collect();
-                    v.sort_unstable_by(|a, b| match (&a.borrow().content, &b.borrow().content) {
-                        (NodeContent::Dir(_), NodeContent::Dir(_))
-                        | (
-                            NodeContent::File(_) | NodeContent::Executable(_),
-                            NodeContent::File(_) | NodeContent::Executable(_),
-                        ) => a.borrow().name.cmp(&b.borrow().name),
-                        (
-                            NodeContent::Dir(_),
-                            NodeContent::File(_) | NodeContent::Executable(_),
-                        ) => std::cmp::Ordering::Less,
-                        (
-                            NodeContent::File(_) | NodeContent::Executable(_),
-                            NodeContent::Dir(_),
-                        ) => std::cmp::Ordering::Greater,
-                    });
+        let column_sizes = rows
+            .iter()
+            .map(|cols| cols.each_ref().map(|col| col.len()))
+            .fold([8, 0, 0, 8, 8, 8], |acc, e| {
+                [
+                    std::cmp::max(acc[0], e[0]),
+                    std::cmp::max(acc[1], e[1]),
+                    std::cmp::max(acc[2], e[2]),
+                    std::cmp::max(acc[3], e[3]),
+                    std::cmp::max(acc[4], e[4]),
+                    std::cmp::max(acc[5], e[5]),
+                ]
+            })
+            .map(|x| x + 2);
+        let column_pad_right = [true, true, true, false, false, true];
+        for columns in rows {
+            for ((column, size), pad_right) in
+                columns.iter().zip(column_sizes).zip(column_pad_right)
+            {
+                if pad_right {
+                    write!(io.stdout, "{column:<size$}")?;
+                } else {
+                    write!(io.stdout, "{column:>size$} ")?;
+                }
+            }
+            writeln!(io.stdout)?;
+        }
+        Ok(())
+    }
 
-                    let rows = v
-                        .iter()
-                        .map(|node| {
-                            [
-                                format!(
-                                    "{}{}{}",
-                                    if node.is_dir() { 'd' } else { '-' },
-                                    node.borrow().owner_security,
-                                    node.borrow().other_security
-                                ),
-                                format!("{}", node.borrow().date),
-                                if let fs::NodeContent::File(f) = &node.borrow().content {
-                                    f.content.len().to_string()
-                                } else {
-                                    "".to_string()
-                                },
-                                node.borrow().name.clone(),
-                            ]
-                        })
-                        .collect::<Vec<[String; 4]>>();
+    /// Lists the directory `node`, shown as `name`, headed by its name if
+    /// `header`, then every directory in it if recursive.
+    fn list_dir(
+        &self,
+        node: &Node,
+        name: &str,
+        header: bool,
+        io: &mut Stdio,
+    ) -> std::io::Result<ExitStatus> {
+        if let Err(e) = self.computer.check_access(node, SecurityMode::Read) {
+            writeln!(io.stderr, "{e}: \"{name}\"")?;
+            return Ok(ExitStatus::FAILURE);
+        }
+        let mut children = node.as_dir().map_or_else(Vec::new, |dir| dir.children);
+        children.retain(|child| self.all || !child.borrow().name.starts_with('.'));
+        self.sort(&mut children);
 
-                    let column_sizes = rows
-                        .iter()
-                        .map(|cols| cols.each_ref().map(|col| col.len()))
-                        .fold([8, 8, 8, 8], |acc, e| {
-                            [
-                                std::cmp::max(acc[0], e[0]),
-                                std::cmp::max(acc[1], e[1]),
-                                std::cmp::max(acc[2], e[2]),
-                                std::cmp::max(acc[3], e[3]),
-                            ]
-                        })
-                        .map(|x| x + 2);
-                    let column_pad_right = [true, false, false, true];
-                    for columns in rows {
-                        for ((column, size), pad_right) in
-                            columns.iter().zip(column_sizes).zip(column_pad_right)
-                        {
-                            if pad_right {
-                                print!("{column:<size$}");
-                            } else {
-                                print!("{column:>size$} ");
-                            }
-                        }
-                        println!();
+        if header {
+            writeln!(io.stdout, "{name}:")?;
+        }
+        self.write_nodes(&children, io)?;
+        if !self.recursive {
+            return Ok(ExitStatus::SUCCESS);
+        }
+
+        let mut status = ExitStatus::SUCCESS;
+        for child in children.iter().filter(|child| child.is_dir()) {
+            if self.g.interrupted() {
+                return Ok(ExitStatus::INTERRUPTED);
+            }
+            let child_name = format!("{}/{}", name.trim_end_matches('/'), child.borrow().name);
+            writeln!(io.stdout)?;
+            match self.list_dir(child, &child_name, true, io)? {
+                ExitStatus::SUCCESS => {}
+                ExitStatus::INTERRUPTED => return Ok(ExitStatus::INTERRUPTED),
+                failure => status = failure,
+            }
+        }
+        Ok(status)
+    }
+}
+
+pub const LS: Subprocess = {
+    struct Ls;
+    impl SubprocessFn for Ls {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let computer = g.current_computer();
+            let listing = Listing {
+                g,
+                computer: &computer,
+                long: args.flag("long"),
+                all: args.flag("all"),
+                by_time: args.flag("time"),
+                recursive: args.flag("recursive"),
+            };
+
+            let here = [".".to_string()];
+            let paths = if args.positionals.is_empty() {
+                &here[..]
+            } else {
+                &args.positionals[..]
+            };
+            let header = paths.len() > 1 || listing.recursive;
+            let mut status = ExitStatus::SUCCESS;
+            let mut listed = false;
+            for path in paths {
+                let node = match computer.lookup(&computer.parse_path(path)) {
+                    Ok(node) => node,
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{path}\"")?;
+                        status = ExitStatus::FAILURE;
+                        continue;
                     }
+                };
+                if listed {
+                    writeln!(io.stdout)?;
+                }
+                listed = true;
+                if !node.is_dir() {
+                    listing.write_nodes(&[node], io)?;
+                    continue;
+                }
+                match listing.list_dir(&node, path, header, io)? {
+                    ExitStatus::SUCCESS => {}
+                    ExitStatus::INTERRUPTED => return Ok(ExitStatus::INTERRUPTED),
+                    failure => status = failure,
                 }
-            } else {
-                println!("\"{dir}\" is not a directory");
             }
-            Ok(())
+            Ok(status)
         }
     }
 
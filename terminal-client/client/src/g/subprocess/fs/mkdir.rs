@@ -0,0 +1,64 @@
+use crate::g::{
+    fs::{FsError, Path},
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "mkdir",
+    title: Some("Make Directory"),
+    description: "creates directories.",
+    about: "Creates each dir.",
+    opts: &[Opt::flag(
+        'p',
+        "parents",
+        "also creates missing parents, and allows dirs that exist",
+    )],
+    args: &[Positional::one_or_more("dir")],
+};
+
+pub const MKDIR: Subprocess = {
+    struct Mkdir;
+    impl SubprocessFn for Mkdir {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let parents = args.flag("parents");
+
+            let computer = g.current_computer();
+            let mut status = ExitStatus::SUCCESS;
+            for dir in &args.positionals {
+                let path = computer.parse_path(dir);
+                let res = if parents {
+                    (1..=path.0.len()).try_for_each(|len| {
+                        let prefix = Path::new(path.0[..len].to_vec());
+                        match computer.create_dir(&prefix) {
+                            Err(FsError::AlreadyExists) => match computer.lookup(&prefix) {
+                                Ok(node) if node.is_dir() => Ok(()),
+                                _ => Err(FsError::NotDirectory),
+                            },
+                            res => res,
+                        }
+                    })
+                } else {
+                    computer.create_dir(&path)
+                };
+                if let Err(e) = res {
+                    writeln!(io.stderr, "{e}: \"{dir}\"")?;
+                    status = ExitStatus::FAILURE;
+                }
+            }
+            Ok(status)
+        }
+    }
+    &Mkdir
+};
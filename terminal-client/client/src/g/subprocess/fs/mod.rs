@@ -2,10 +2,73 @@ use super::Subprocess;
 
 mod cat;
 mod cd;
+mod chmod;
+mod chown;
+mod cp;
+mod cut;
+mod edit;
+mod find;
+mod grep;
+mod head;
+mod less;
+mod locate;
 mod ls;
+mod mkdir;
+mod mv;
+mod rm;
+mod sort;
+mod tail;
+mod text;
+mod touch;
+mod uniq;
+mod updatedb;
+mod wc;
 
 pub use cat::*;
 pub use cd::*;
+pub use chmod::*;
+pub use chown::*;
+pub use cp::*;
+pub use cut::*;
+pub use edit::*;
+pub use find::*;
+pub use grep::*;
+pub use head::*;
+pub use less::*;
+pub use locate::*;
 pub use ls::*;
+pub use mkdir::*;
+pub use mv::*;
+pub use rm::*;
+pub use sort::*;
+pub use tail::*;
+pub use touch::*;
+pub use uniq::*;
+pub use updatedb::*;
+pub use wc::*;
 
-pub const DEFAULT: &[(&str, Subprocess)] = &[("ls", LS), ("cd", CD), ("cat", CAT)];
+pub const DEFAULT: &[(&str, Subprocess)] = &[
+    ("ls", LS),
+    ("cd", CD),
+    ("cat", CAT),
+    ("chmod", CHMOD),
+    ("chown", CHOWN),
+    ("mkdir", MKDIR),
+    ("touch", TOUCH),
+    ("rm", RM),
+    ("mv", MV),
+    ("cp", CP),
+    ("edit", EDIT),
+    ("grep", GREP),
+    ("head", HEAD),
+    ("tail", TAIL),
+    ("wc", WC),
+    ("sort", SORT),
+    ("uniq", UNIQ),
+    ("cut", CUT),
+    ("less", LESS),
+    ("more", LESS),
+    ("find", FIND),
+    ("locate", LOCATE),
+    ("updatedb", UPDATEDB),
+];
@@ -0,0 +1,54 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "mv",
+    title: Some("Move"),
+    description: "moves or renames files and directories.",
+    about: concat!(
+        "Renames src to dest, or moves it into dest if that is a directory.\n",
+        "Several srcs are moved into dest, which must then be a directory."
+    ),
+    opts: &[],
+    args: &[Positional::one_or_more("src"), Positional::one("dest")],
+};
+
+pub const MV: Subprocess = {
+    struct Mv;
+    impl SubprocessFn for Mv {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            // parsing ensures a src and a dest
+            let (dest, srcs) = args.positionals.split_last().unwrap();
+
+            let computer = g.current_computer();
+            let dest_path = computer.parse_path(dest);
+            if srcs.len() > 1 && !computer.lookup(&dest_path).is_ok_and(|n| n.is_dir()) {
+                writeln!(io.stderr, "Not a directory: \"{dest}\"")?;
+                return Ok(ExitStatus::FAILURE);
+            }
+
+            let mut status = ExitStatus::SUCCESS;
+            for src in srcs {
+                if let Err(e) = computer.rename(&computer.parse_path(src), &dest_path) {
+                    writeln!(io.stderr, "{e}: \"{src}\"")?;
+                    status = ExitStatus::FAILURE;
+                }
+            }
+            Ok(status)
+        }
+    }
+    &Mv
+};
@@ -0,0 +1,56 @@
+use crate::g::{
+    fs::FsError,
+    subprocess::{
+        args::{ArgError, ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "rm",
+    title: Some("Remove"),
+    description: "deletes files and directories.",
+    about: "Deletes each path. There is no undo.",
+    opts: &[
+        Opt::flag('r', "recursive", "deletes directories and all in them"),
+        Opt::flag('f', "force", "ignores paths that do not exist"),
+    ],
+    args: &[Positional::many("path")],
+};
+
+pub const RM: Subprocess = {
+    struct Rm;
+    impl SubprocessFn for Rm {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let (recursive, force) = (args.flag("recursive"), args.flag("force"));
+            // with -f, nothing to delete is not an error
+            if args.positionals.is_empty() && !force {
+                return ARGS.usage_error(ArgError::MissingArgument("path"), io);
+            }
+
+            let computer = g.current_computer();
+            let mut status = ExitStatus::SUCCESS;
+            for path in &args.positionals {
+                match computer.remove(&computer.parse_path(path), recursive) {
+                    Ok(()) => {}
+                    Err(FsError::DoesNotExist) if force => {}
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{path}\"")?;
+                        status = ExitStatus::FAILURE;
+                    }
+                }
+            }
+            Ok(status)
+        }
+    }
+    &Rm
+};
@@ -0,0 +1,88 @@
+use std::cmp::Ordering;
+
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+use super::text::read_inputs;
+
+/// The number a line starts with, for `sort -n`. Lines without one count as 0.
+fn leading_number(line: &str) -> f64 {
+    let line = line.trim_start();
+    let end = line
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && c == '-')))
+        .map_or(line.len(), |(i, _)| i);
+    line[..end].parse().unwrap_or(0.0)
+}
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "sort",
+    title: None,
+    description: "sorts lines of text.",
+    about: "Prints the lines of all the files, or of stdin, in order.",
+    opts: &[
+        Opt::flag('r', "reverse", "reverses the order"),
+        Opt::flag('n', "numeric-sort", "sorts by the number lines start with"),
+        Opt::flag('f', "ignore-case", "ignores case"),
+        Opt::flag('u', "unique", "prints lines that sort the same only once"),
+    ],
+    args: &[Positional::many("file")],
+};
+
+pub const SORT: Subprocess = {
+    struct Sort;
+    impl SubprocessFn for Sort {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let files = &args.positionals;
+            let reverse = args.flag("reverse");
+            let numeric = args.flag("numeric-sort");
+            let ignore_case = args.flag("ignore-case");
+            let unique = args.flag("unique");
+
+            let (inputs, status) = read_inputs(g, files, false, io)?;
+            let mut lines = inputs
+                .iter()
+                .flat_map(|input| input.content.lines())
+                .collect::<Vec<_>>();
+            let compare = |a: &&str, b: &&str| -> Ordering {
+                if numeric {
+                    leading_number(a).total_cmp(&leading_number(b))
+                } else if ignore_case {
+                    a.to_lowercase().cmp(&b.to_lowercase())
+                } else {
+                    a.cmp(b)
+                }
+            };
+            lines.sort_by(|a, b| {
+                let order = compare(a, b);
+                if reverse {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+            if unique {
+                lines.dedup_by(|a, b| compare(a, b) == Ordering::Equal);
+            }
+
+            for line in lines {
+                writeln!(io.stdout, "{line}")?;
+            }
+            Ok(status)
+        }
+    }
+    &Sort
+};
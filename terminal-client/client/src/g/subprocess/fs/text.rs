@@ -0,0 +1,99 @@
+//! What the text utilities (`grep`, `head`, `sort`...) share: reading the
+//! files they are given, or else stdin.
+
+use crate::g::{
+    computer::Computer,
+    fs::{FsError, Path, SecurityMode},
+    subprocess::{ExitStatus, Stdio},
+    Game,
+};
+
+/// A file, or stdin, read whole.
+pub struct Input {
+    /// As given on the command line, or `(standard input)`.
+    pub name: String,
+    pub content: String,
+}
+
+/// Reads each of `files` as the current user, or stdin if there are none or
+/// for `-`. With `recursive`, directories are read with everything in them.
+/// Files that cannot be read are reported and skipped, and make the status
+/// a failure.
+pub fn read_inputs(
+    g: &Game,
+    files: &[String],
+    recursive: bool,
+    io: &mut Stdio,
+) -> std::io::Result<(Vec<Input>, ExitStatus)> {
+    let computer = g.current_computer();
+    let mut inputs = Vec::new();
+    let mut status = ExitStatus::SUCCESS;
+    if files.is_empty() {
+        inputs.push(read_stdin(g, io)?);
+    }
+    for file in files {
+        if file == "-" {
+            inputs.push(read_stdin(g, io)?);
+            continue;
+        }
+        let mut errors = Vec::new();
+        let path = computer.parse_path(file);
+        read_path(&computer, &path, file, recursive, &mut inputs, &mut errors);
+        for (name, e) in errors {
+            writeln!(io.stderr, "{e}: \"{name}\"")?;
+            status = ExitStatus::FAILURE;
+        }
+    }
+    Ok((inputs, status))
+}
+
+fn read_stdin(g: &Game, io: &mut Stdio) -> std::io::Result<Input> {
+    let mut content = String::new();
+    while io.stdin.read_line(&mut content)? > 0 && !g.interrupted() {}
+    Ok(Input {
+        name: "(standard input)".into(),
+        content,
+    })
+}
+
+/// Reads the file at `path`, or the files under it if it is a directory and
+/// `recursive`. Adds what could not be read to `errors`, by name.
+fn read_path(
+    computer: &Computer,
+    path: &Path,
+    name: &str,
+    recursive: bool,
+    inputs: &mut Vec<Input>,
+    errors: &mut Vec<(String, FsError)>,
+) {
+    let node = match computer.read_file(path) {
+        Ok(content) => {
+            inputs.push(Input {
+                name: name.to_string(),
+                content,
+            });
+            return;
+        }
+        Err(FsError::IsDirectory) if recursive => computer.lookup(path).and_then(|node| {
+            computer.check_access(&node, SecurityMode::RX)?;
+            Ok(node)
+        }),
+        Err(e) => Err(e),
+    };
+    let node = match node {
+        Ok(node) => node,
+        Err(e) => return errors.push((name.to_string(), e)),
+    };
+
+    let children = node.as_dir().map_or_else(Vec::new, |dir| dir.children);
+    for child in children {
+        // executables are not text
+        if child.is_exe() {
+            continue;
+        }
+        let child_name = child.borrow().name.clone();
+        let child_path = path.clone().join(&Path::new(vec![child_name.clone()]));
+        let child_name = format!("{}/{child_name}", name.trim_end_matches('/'));
+        read_path(computer, &child_path, &child_name, true, inputs, errors);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "touch",
+    title: None,
+    description: "updates the dates of files, creating them if needed.",
+    about: "Sets the date of each file to now, or creates it empty.",
+    opts: &[],
+    args: &[Positional::one_or_more("file")],
+};
+
+pub const TOUCH: Subprocess = {
+    struct Touch;
+    impl SubprocessFn for Touch {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+
+            let computer = g.current_computer();
+            let mut status = ExitStatus::SUCCESS;
+            for file in &args.positionals {
+                if let Err(e) = computer.touch(&computer.parse_path(file)) {
+                    writeln!(io.stderr, "{e}: \"{file}\"")?;
+                    status = ExitStatus::FAILURE;
+                }
+            }
+            Ok(status)
+        }
+    }
+    &Touch
+};
@@ -0,0 +1,76 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+use super::text::read_inputs;
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "uniq",
+    title: Some("Unique"),
+    description: "collapses repeated lines.",
+    about: concat!(
+        "Prints the lines of file, or of stdin, printing lines repeated one\n",
+        "after the other only once. Sort them first to collapse them all."
+    ),
+    opts: &[
+        Opt::flag('c', "count", "prefixes lines with their count"),
+        Opt::flag('d', "repeated", "only prints repeated lines"),
+        Opt::flag('u', "unique", "only prints lines that were not repeated"),
+        Opt::flag('i', "ignore-case", "ignores case"),
+    ],
+    args: &[Positional::optional("file")],
+};
+
+pub const UNIQ: Subprocess = {
+    struct Uniq;
+    impl SubprocessFn for Uniq {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let files = &args.positionals;
+            let count = args.flag("count");
+            let repeated = args.flag("repeated");
+            let single = args.flag("unique");
+            let ignore_case = args.flag("ignore-case");
+
+            let (inputs, status) = read_inputs(g, files, false, io)?;
+            let same = |a: &str, b: &str| {
+                if ignore_case {
+                    a.to_lowercase() == b.to_lowercase()
+                } else {
+                    a == b
+                }
+            };
+            let mut groups: Vec<(&str, usize)> = Vec::new();
+            for line in inputs.iter().flat_map(|input| input.content.lines()) {
+                match groups.last_mut() {
+                    Some((last, n)) if same(last, line) => *n += 1,
+                    _ => groups.push((line, 1)),
+                }
+            }
+
+            for (line, n) in groups {
+                if (repeated && n == 1) || (single && n > 1) {
+                    continue;
+                }
+                if count {
+                    writeln!(io.stdout, "{n:>7} {line}")?;
+                } else {
+                    writeln!(io.stdout, "{line}")?;
+                }
+            }
+            Ok(status)
+        }
+    }
+    &Uniq
+};
@@ -0,0 +1,46 @@
+use crate::g::{
+    fs::FsError,
+    locate::locate_db_path,
+    subprocess::{args::ArgSpec, ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo},
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "updatedb",
+    title: None,
+    description: "rebuilds the index searched by locate.",
+    about: concat!(
+        "Records every path of this computer in the index searched by\n",
+        "locate. Only root may run it."
+    ),
+    opts: &[],
+    args: &[],
+};
+
+pub const UPDATEDB: Subprocess = {
+    struct UpdateDb;
+    impl SubprocessFn for UpdateDb {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            if let Err(e) = ARGS.parse(&args) {
+                return ARGS.usage_error(e, io);
+            }
+
+            let computer = g.current_computer();
+            let res = if computer.current_user().is_root() {
+                computer.update_locate_db()
+            } else {
+                Err(FsError::PermissionDenied)
+            };
+            if let Err(e) = res {
+                writeln!(io.stderr, "{e}: \"{}\"", locate_db_path())?;
+                return Ok(ExitStatus::FAILURE);
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+    &UpdateDb
+};
@@ -0,0 +1,87 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+use super::text::read_inputs;
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "wc",
+    title: Some("Word count"),
+    description: "counts the lines, words and bytes of files.",
+    about: concat!(
+        "Prints how many lines, words and bytes each file, or stdin, has,\n",
+        "or only the counts asked for. With several files, the total is\n",
+        "printed last."
+    ),
+    opts: &[
+        Opt::flag('l', "lines", "prints the lines"),
+        Opt::flag('w', "words", "prints the words"),
+        Opt::flag('c', "bytes", "prints the bytes"),
+    ],
+    args: &[Positional::many("file")],
+};
+
+pub const WC: Subprocess = {
+    struct Wc;
+    impl SubprocessFn for Wc {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let files = &args.positionals;
+            let mut shown = ["lines", "words", "bytes"].map(|count| args.flag(count));
+            if shown == [false; 3] {
+                shown = [true; 3];
+            }
+
+            let (inputs, status) = read_inputs(g, files, false, io)?;
+            let mut counts = inputs
+                .iter()
+                .map(|input| {
+                    let content = &input.content;
+                    let counts = [
+                        content.matches('\n').count(),
+                        content.split_whitespace().count(),
+                        content.len(),
+                    ];
+                    (counts, (!files.is_empty()).then_some(input.name.as_str()))
+                })
+                .collect::<Vec<_>>();
+            if counts.len() > 1 {
+                let mut total = [0; 3];
+                for (c, _) in &counts {
+                    for (total, c) in total.iter_mut().zip(c) {
+                        *total += c;
+                    }
+                }
+                counts.push((total, Some("total")));
+            }
+
+            for (counts, name) in counts {
+                let mut line = counts
+                    .iter()
+                    .zip(shown)
+                    .filter(|(_, shown)| *shown)
+                    .map(|(count, _)| format!("{count:>7}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if let Some(name) = name {
+                    line.push(' ');
+                    line.push_str(name);
+                }
+                writeln!(io.stdout, "{line}")?;
+            }
+            Ok(status)
+        }
+    }
+    &Wc
+};
@@ -2,9 +2,14 @@ use std::fmt::Debug;
 
 use super::Game;
 
+pub mod args;
+pub mod crack;
 pub mod fs;
 pub mod sys;
 pub mod myhealth;
+mod stdio;
+
+pub use stdio::*;
 
 #[derive(Debug, Clone, Default, PartialEq, PartialOrd, Eq, Ord)]
 pub struct SubprocessInfo {
@@ -18,11 +23,42 @@ pub trait SubprocessFn {
         Default::default()
     }
 
-    fn run(&self, g: &Game, args: Vec<String>) -> std::io::Result<()>;
+    fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus>;
+
+    /// Completes the argument `word`, typed after `args`, when tab is pressed.
+    /// Candidates are whole words, unquoted. `None` falls back to completing paths.
+    fn complete(&self, _g: &Game, _args: &[String], _word: &str) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Identifies the implementation, so that it can be found again in `all()`.
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 pub type Subprocess = &'static dyn SubprocessFn;
 
+/// Every built-in executable with its default name.
+pub fn all() -> impl Iterator<Item = &'static (&'static str, Subprocess)> + Clone {
+    std::iter::empty()
+        .chain(sys::DEFAULT)
+        .chain(fs::DEFAULT)
+        .chain(myhealth::DEFAULT)
+        .chain(crack::DEFAULT)
+}
+
+/// The name under which `subprocess` is listed in `all()`.
+pub fn name_of(subprocess: Subprocess) -> Option<&'static str> {
+    all()
+        .find(|(_, s)| s.type_name() == subprocess.type_name())
+        .map(|(name, _)| *name)
+}
+
+pub fn find(name: &str) -> Option<Subprocess> {
+    all().find(|(n, _)| *n == name).map(|(_, s)| *s)
+}
+
 impl Debug for dyn SubprocessFn {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let info = self.info();
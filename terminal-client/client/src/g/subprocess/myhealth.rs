@@ -2,7 +2,7 @@ use crossterm::{event::{self, KeyCode, KeyEventKind}, terminal::{self, EnterAlte
 use ratatui::{layout::{Constraint, Layout}, prelude::CrosstermBackend, style::{Color, Modifier, Style, Stylize}, text::{Line, Span}, widgets::{Block, Borders, List, ListItem, ListState, Paragraph, StatefulWidget, Tabs, Widget}};
 use tui_big_text::{BigText, PixelSize};
 
-use crate::g::subprocess::SubprocessFn;
+use crate::g::subprocess::{is_interrupt_key, ExitStatus, Stdio, SubprocessFn};
 
 use super::Subprocess;
 
@@ -128,7 +128,7 @@ pub const MYHEALTH: Subprocess = {
     struct MyHealth;
 
     impl SubprocessFn for MyHealth {
-        fn run(&self, g: &crate::g::Game, args: Vec<String>) -> std::io::Result<()> {
+        fn run(&self, g: &crate::g::Game, args: Vec<String>, _io: &mut Stdio) -> std::io::Result<ExitStatus> {
             let mut state = MyHealthState {
                 records: vec![
                     MyHealthRecord { 
@@ -189,6 +189,10 @@ pub const MYHEALTH: Subprocess = {
                 if event::poll(std::time::Duration::from_millis(16))? {
                    if let event::Event::Key(k) = event::read()? {
                         if k.kind == KeyEventKind::Press {
+                            if is_interrupt_key(&k) {
+                                g.interrupt();
+                                break;
+                            }
                             match k.code {
                                 KeyCode::Char('q') => { break; },
                                 KeyCode::Esc if state.selecting_tabs => { break; },
@@ -227,7 +231,10 @@ pub const MYHEALTH: Subprocess = {
             terminal::disable_raw_mode()?;
             std::io::stdout().execute(LeaveAlternateScreen)?;
 
-            Ok(())
+            if g.interrupted() {
+                return Ok(ExitStatus::INTERRUPTED);
+            }
+            Ok(ExitStatus::SUCCESS)
         }
     }
 
@@ -0,0 +1,77 @@
+use std::io::{BufRead, BufReader, Write};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// The standard streams of a running subprocess.
+///
+/// Subprocesses should read and write through these instead of the
+/// terminal, so that the shell can capture or redirect them.
+pub struct Stdio<'a> {
+    pub stdin: &'a mut dyn BufRead,
+    pub stdout: &'a mut dyn Write,
+    pub stderr: &'a mut dyn Write,
+}
+
+impl Stdio<'_> {
+    /// Runs `f` with the streams connected to the real terminal.
+    pub fn terminal<R>(f: impl FnOnce(&mut Stdio) -> R) -> R {
+        let mut stdin = BufReader::new(std::io::stdin());
+        let mut stdout = std::io::stdout();
+        let mut stderr = std::io::stderr();
+        f(&mut Stdio {
+            stdin: &mut stdin,
+            stdout: &mut stdout,
+            stderr: &mut stderr,
+        })
+    }
+}
+
+/// Whether `key` is Ctrl-C. In raw mode, as in TUI apps, Ctrl-C arrives as
+/// this key instead of interrupting; see `Game::interrupt`.
+pub fn is_interrupt_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// The status a subprocess exits with. Zero means success.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExitStatus(pub i32);
+
+#[allow(dead_code)]
+impl ExitStatus {
+    pub const SUCCESS: Self = Self(0);
+    pub const FAILURE: Self = Self(1);
+    /// The executable was called with invalid arguments.
+    pub const USAGE: Self = Self(2);
+    /// The executable was found, but could not be run.
+    pub const NOT_EXECUTABLE: Self = Self(126);
+    /// No executable with that name was found.
+    pub const NOT_FOUND: Self = Self(127);
+    /// The player pressed Ctrl-C.
+    pub const INTERRUPTED: Self = Self(130);
+    /// The process was stopped with `kill`.
+    pub const KILLED: Self = Self(137);
+
+    pub fn success(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn code(self) -> i32 {
+        self.0
+    }
+}
+
+impl From<bool> for ExitStatus {
+    fn from(success: bool) -> Self {
+        if success {
+            Self::SUCCESS
+        } else {
+            Self::FAILURE
+        }
+    }
+}
+
+impl std::fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
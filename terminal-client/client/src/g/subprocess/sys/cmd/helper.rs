@@ -0,0 +1,236 @@
+//! Line editing support for the interactive shell.
+
+use std::borrow::Cow;
+
+use crossterm::style::Stylize;
+use rustyline::{
+    completion::Completer, highlight::Highlighter, hint::HistoryHinter, Helper, Hinter, Validator,
+};
+
+use crate::g::Game;
+
+use super::{
+    script::KEYWORDS,
+    shell::{Aliases, BUILTINS},
+};
+
+/// Completes, highlights and hints from history as the player types.
+#[derive(Helper, Validator, Hinter)]
+pub struct RlHelper<'a>(
+    pub &'a Game,
+    #[rustyline(Hinter)] HistoryHinter,
+    /// Those of the shell, which count as commands.
+    Aliases,
+);
+
+impl<'a> RlHelper<'a> {
+    pub fn new(g: &'a Game, aliases: Aliases) -> Self {
+        Self(g, HistoryHinter::new(), aliases)
+    }
+}
+
+/// A word of a partly typed line.
+#[derive(Debug, Default)]
+struct Word {
+    /// Where the word starts and ends in the line.
+    start: usize,
+    end: usize,
+    /// The word without quotes and escapes.
+    text: String,
+    /// The quote left open at the end of the line, and where it starts.
+    open_quote: Option<(usize, char)>,
+}
+
+/// Splits a partly typed line into the words of each command, starting a new
+/// command after `|`, `;` and `&`. Unlike `parse::tokenize`, this keeps track
+/// of where each word is, and does not expand anything.
+fn commands(line: &str) -> Vec<Vec<Word>> {
+    let mut commands = vec![Vec::new()];
+    let mut word: Option<Word> = None;
+    let mut quote: Option<(usize, char)> = None;
+    let mut escaping = false;
+
+    for (i, ch) in line.char_indices() {
+        if escaping {
+            escaping = false;
+        } else {
+            match quote {
+                Some((_, q)) if ch == q => {
+                    quote = None;
+                    continue;
+                }
+                Some((_, '"')) if ch == '\\' => {
+                    escaping = true;
+                    continue;
+                }
+                Some(_) => {}
+                None if ch.is_whitespace() || "|;&<>".contains(ch) => {
+                    if let Some(mut word) = word.take() {
+                        word.end = i;
+                        commands.last_mut().unwrap().push(word);
+                    }
+                    if "|;&".contains(ch) {
+                        commands.push(Vec::new());
+                    }
+                    continue;
+                }
+                None => {
+                    word.get_or_insert_with(|| Word {
+                        start: i,
+                        ..Default::default()
+                    });
+                    match ch {
+                        '\\' => escaping = true,
+                        '"' | '\'' => quote = Some((i, ch)),
+                        _ => {}
+                    }
+                    if matches!(ch, '\\' | '"' | '\'') {
+                        continue;
+                    }
+                }
+            }
+        }
+        if let Some(word) = &mut word {
+            word.text.push(ch);
+        }
+    }
+
+    if let Some(mut word) = word {
+        word.end = line.len();
+        word.open_quote = quote;
+        commands.last_mut().unwrap().push(word);
+    }
+    commands
+}
+
+impl RlHelper<'_> {
+    /// Styles one word of the line: a command green if it can be run (or is
+    /// an alias) and red otherwise, an existing path underlined, and an unterminated quote yellow.
+    fn highlight_word(&self, line: &str, word: &Word, is_command: bool) -> String {
+        let computer = self.0.current_computer();
+        let text = word.text.as_str();
+        let split = word.open_quote.map_or(word.end, |(i, _)| i);
+        let (typed, unterminated) = (&line[word.start..split], &line[split..word.end]);
+
+        let styled = if is_command {
+            let known = BUILTINS.contains(&text)
+                || KEYWORDS.contains(&text)
+                || self.2.borrow().contains_key(text)
+                || computer.which(text).is_some();
+            if known {
+                typed.green()
+            } else {
+                typed.red()
+            }
+        } else if !text.is_empty() && computer.lookup(&computer.parse_path(text)).is_ok() {
+            typed.underlined()
+        } else {
+            typed.stylize()
+        };
+        match unterminated {
+            "" => styled.to_string(),
+            unterminated => format!("{styled}{}", unterminated.yellow()),
+        }
+    }
+}
+
+impl Highlighter for RlHelper<'_> {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        let mut out = String::with_capacity(line.len());
+        let mut written = 0;
+        for command in commands(line) {
+            let mut is_command = true;
+            for word in &command {
+                out.push_str(&line[written..word.start]);
+                out.push_str(&self.highlight_word(line, word, is_command));
+                written = word.end;
+                // the word after these keywords is a command too
+                is_command &= ["if", "then", "elif", "else", "do"].contains(&word.text.as_str());
+            }
+        }
+        out.push_str(&line[written..]);
+        Cow::Owned(out)
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dark_grey().to_string())
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _forced: bool) -> bool {
+        true
+    }
+}
+
+/// Writes `candidate` so that it reads back as itself, in the same quotes as
+/// the word being completed. Quotes are closed unless `candidate` is a
+/// directory, which likely is not complete yet.
+fn quote(candidate: &str, quote: Option<char>) -> String {
+    let mut out = String::new();
+    match quote {
+        Some('\'') => {
+            out.push('\'');
+            out.push_str(&candidate.replace('\'', "'\\''"));
+        }
+        Some(q) => {
+            out.push(q);
+            for ch in candidate.chars() {
+                if matches!(ch, '"' | '\\' | '$') {
+                    out.push('\\');
+                }
+                out.push(ch);
+            }
+        }
+        None => {
+            for ch in candidate.chars() {
+                if ch.is_whitespace() || "\"'\\$|;&<>*?[#".contains(ch) {
+                    out.push('\\');
+                }
+                out.push(ch);
+            }
+        }
+    }
+    if let Some(q) = quote.filter(|_| !candidate.ends_with('/')) {
+        out.push(q);
+    }
+    out
+}
+
+impl Completer for RlHelper<'_> {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Self::Candidate>)> {
+        let line = &line[..pos];
+        let mut command = commands(line).pop().unwrap_or_default();
+        let word = match command.last() {
+            Some(word) if word.end == line.len() => command.pop().unwrap(),
+            _ => Word {
+                start: line.len(),
+                end: line.len(),
+                ..Default::default()
+            },
+        };
+        let args = command.into_iter().map(|w| w.text).collect::<Vec<_>>();
+
+        let computer = self.0.current_computer();
+        let candidates = match args.split_first() {
+            None if !word.text.contains('/') => computer.complete_exe(&word.text),
+            None => computer.complete_path(&word.text, false),
+            Some((name, args)) => computer
+                .which(name)
+                .and_then(|(_, node)| node.as_exe())
+                .and_then(|exe| exe.complete(self.0, args, &word.text))
+                .unwrap_or_else(|| computer.complete_path(&word.text, false)),
+        };
+
+        let open_quote = word.open_quote.map(|(_, q)| q);
+        Ok((
+            word.start,
+            candidates.iter().map(|c| quote(c, open_quote)).collect(),
+        ))
+    }
+}
@@ -0,0 +1,263 @@
+use rustyline::error::ReadlineError;
+
+use crate::{
+    g::{
+        cron::mail_path,
+        subprocess::{ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo},
+        Game,
+    },
+    log,
+};
+
+mod helper;
+mod pager;
+mod parse;
+mod script;
+mod shell;
+
+use helper::RlHelper;
+use pager::AutoPager;
+use shell::Shell;
+
+/// Expands the escapes in `PS1`:
+///
+/// - `\u` user, `\h`/`\H` host, `\w` cwd, `\W` last cwd component
+/// - `\d` date, `\t` 24h time, `\T` 12h time, `\A` hours and minutes
+/// - `\?` exit status of the last command, `\$` `#` for root and `$` otherwise
+/// - `\e` and `\033` escape (for colours), `\n` newline, `\\` backslash
+/// - `\[` and `\]` are accepted and ignored
+fn format_prompt(g: &Game, ps1: &str, status: ExitStatus) -> String {
+    let computer = g.current_computer();
+    let now = g.clock.now();
+
+    let mut out = String::new();
+    let mut chars = ps1.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('u') => out.push_str(&computer.current_user().name),
+            Some('h') | Some('H') => out.push_str(&computer.name),
+            Some('w') => out.push_str(&computer.cwd.borrow().to_string()),
+            Some('W') => match computer.cwd.borrow().0.last() {
+                Some(name) => out.push_str(name),
+                None => out.push('/'),
+            },
+            Some('d') => out.push_str(&now.format("%a %b %d").to_string()),
+            Some('t') => out.push_str(&now.format("%H:%M:%S").to_string()),
+            Some('T') => out.push_str(&now.format("%I:%M:%S").to_string()),
+            Some('A') => out.push_str(&now.format("%H:%M").to_string()),
+            Some('?') => out.push_str(&status.to_string()),
+            Some('$') => out.push(if computer.current_user().is_root() {
+                '#'
+            } else {
+                '$'
+            }),
+            Some('e') => out.push('\x1b'),
+            Some('0') => {
+                let digits: String = std::iter::from_fn(|| chars.next_if(|c| c.is_digit(8)))
+                    .take(2)
+                    .collect();
+                match u8::from_str_radix(&digits, 8) {
+                    Ok(code) => out.push(code as char),
+                    Err(_) => out.push('\0'),
+                }
+            }
+            Some('n') => out.push('\n'),
+            Some('[') | Some(']') => {}
+            Some(ch) => {
+                out.push('\\');
+                out.push(ch);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Reads and runs command lines until the session ends, paging their output
+/// for `session`.
+fn interact(
+    g: &Game,
+    rl: &mut rustyline::Editor<RlHelper, rustyline::history::DefaultHistory>,
+    shell: &mut Shell,
+    session: u64,
+    io: &mut Stdio,
+) -> std::io::Result<()> {
+    // `ssh` and `logout` end the session on the computer it started on,
+    // and `ssh` in a nested shell ends the outer ones too
+    let index = g.current_computer_index();
+    let computer = g.current_computer();
+    let pid = g.current_pid();
+
+    while !computer.should_quit.get() && g.current_computer_index() == index {
+        shell.report_jobs(io)?;
+        g.run_cron();
+        if g.take_new_mail() {
+            let mailbox = mail_path(&computer.current_user().name);
+            writeln!(io.stdout, "You have new mail in {mailbox}")?;
+        }
+
+        let ps1 = g
+            .current_computer()
+            .env
+            .borrow()
+            .get(&"PS1".to_string())
+            .cloned()
+            .unwrap_or(DEFAULT_PS1.into());
+
+        let line = match rl.readline(&format_prompt(g, &ps1, shell.status)) {
+            Ok(line) => line,
+            // Ctrl-C cancels the line being typed
+            Err(ReadlineError::Interrupted) => {
+                shell.status = ExitStatus::INTERRUPTED;
+                continue;
+            }
+            // Ctrl-D ends the session like `exit`
+            Err(ReadlineError::Eof) => {
+                writeln!(io.stdout, "exit")?;
+                break;
+            }
+            Err(e) => return Err(std::io::Error::other(e)),
+        };
+
+        let line = match parse::expand_history(&line, &computer.history()) {
+            Ok(Some(expanded)) => {
+                writeln!(io.stdout, "{expanded}")?;
+                expanded
+            }
+            Ok(None) => line,
+            Err(e) => {
+                writeln!(io.stderr, "{e}")?;
+                shell.status = ExitStatus::FAILURE;
+                continue;
+            }
+        };
+        if !line.trim().is_empty() {
+            let _ = rl.add_history_entry(&line);
+            let _ = computer.add_history(&line);
+        }
+
+        g.clear_interrupt();
+        let mut pager = AutoPager::new(g, session, &mut *io.stdout);
+        let res = shell.run_source(
+            &line,
+            &mut Stdio {
+                stdin: &mut *io.stdin,
+                stdout: &mut pager,
+                stderr: &mut *io.stderr,
+            },
+        );
+        let held = pager.into_held();
+        res?;
+        if g.interrupted() {
+            writeln!(io.stdout)?;
+        } else if let Some(held) = held {
+            held.page(g, io)?;
+        }
+
+        if let Err(e) = g.save_to_disk() {
+            log!("Could not save game: {e:?}");
+        }
+
+        if shell.exit.is_some() {
+            break;
+        }
+        if pid.is_some_and(|pid| !computer.processes.borrow().contains(pid)) {
+            shell.exit = Some(ExitStatus::KILLED);
+            break;
+        }
+    }
+    Ok(())
+}
+
+const DEFAULT_PS1: &str = "\\u@\\H \\w$ ";
+
+pub const CMD: Subprocess = {
+    pub struct Cmd;
+
+    impl SubprocessFn for Cmd {
+        fn info(&self) -> SubprocessInfo {
+            SubprocessInfo {
+                name: Some("Command Shell".into()),
+                description: Some("runs commands and scripts.".into()),
+                help_text: Some(
+                    concat!(
+                        "cmd [script [args...]]\n",
+                        "cmd -c command [name [args...]]\n",
+                        "\tWithout a script or command, reads commands interactively.\n",
+                        "\tScripts may use $1, $@, $#, if/elif/else/fi and for/do/done.\n",
+                        "\tBuiltins: source file [args...], exit [status],\n",
+                        "\talias [name[=value]...], unalias -a | name...\n",
+                        "\tInteractive sessions first source ~/.cmdrc."
+                    )
+                    .into(),
+                ),
+            }
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            if args.first().is_some_and(|arg| arg == "-c") {
+                let Some(command) = args.get(1) else {
+                    let _ = g.start_exe_from_path("help", vec!["cmd".into()], io);
+                    return Ok(ExitStatus::USAGE);
+                };
+                let name = args.get(2).cloned().unwrap_or_else(|| "cmd".into());
+                let mut shell = Shell::new(
+                    g,
+                    std::iter::once(name)
+                        .chain(args.iter().skip(3).cloned())
+                        .collect(),
+                );
+                shell.run_source(command, io)?;
+                shell.report_jobs(io)?;
+                return Ok(shell.exit.unwrap_or(shell.status));
+            }
+
+            if let Some(script) = args.first() {
+                let computer = g.current_computer();
+                let content = match computer.read_file(&computer.parse_path(script)) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{script}\"")?;
+                        return Ok(ExitStatus::NOT_FOUND);
+                    }
+                };
+
+                let mut shell = Shell::new(g, args);
+                shell.run_source(&content, io)?;
+                shell.report_jobs(io)?;
+                return Ok(shell.exit.unwrap_or(shell.status));
+            }
+
+            let mut shell = Shell::new(g, vec!["cmd".into()]);
+            let rl_helper = RlHelper::new(g, shell.aliases());
+            let rl_config = rustyline::Config::builder()
+                .auto_add_history(false)
+                .completion_type(rustyline::CompletionType::List)
+                .build();
+
+            let mut rl: rustyline::Editor<RlHelper, rustyline::history::DefaultHistory> =
+                rustyline::Editor::with_config(rl_config).map_err(std::io::Error::other)?;
+
+            let computer = g.current_computer();
+            rl.set_helper(Some(rl_helper));
+            for line in computer.history() {
+                let _ = rl.add_history_entry(line);
+            }
+
+            g.clear_interrupt();
+            shell.source_rc(io)?;
+            let previous_session = g.session();
+            let session = g.start_session();
+            let res = interact(g, &mut rl, &mut shell, session, io);
+            g.end_session(previous_session);
+            res?;
+            computer.should_quit.set(false);
+            Ok(shell.exit.unwrap_or(shell.status))
+        }
+    }
+    &Cmd
+};
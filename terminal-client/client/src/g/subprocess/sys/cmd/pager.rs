@@ -0,0 +1,151 @@
+//! Paging of command output too long for the screen.
+
+use std::io::Write;
+
+use crate::g::{subprocess::Stdio, Game};
+
+/// What pages output when `PAGER` is not set. An empty `PAGER` turns paging
+/// off.
+const DEFAULT_PAGER: &str = "less";
+
+/// Stands in for the terminal while an interactive command line runs. Output
+/// is shown as it comes until it would scroll off the screen, then held back
+/// to be paged once the command line is done, see [`Held::page`].
+pub struct AutoPager<'a> {
+    g: &'a Game,
+    out: &'a mut dyn Write,
+    /// The session this pages for. Once another one starts, as with `ssh`,
+    /// it owns the screen and output only passes through for the rest of the
+    /// command line.
+    session: u64,
+    /// The terminal size, or `None` when not paging.
+    size: Option<(usize, usize)>,
+    /// Everything written so far, and how much of it was shown.
+    output: Vec<u8>,
+    shown: usize,
+    /// Where the output ended on screen, as a row and a column.
+    row: usize,
+    col: usize,
+}
+
+impl<'a> AutoPager<'a> {
+    pub fn new(g: &'a Game, session: u64, out: &'a mut dyn Write) -> Self {
+        let paging = pager(g).is_some();
+        // a terminal that reports no size, like some pipes, is never paged
+        let size = crossterm::terminal::size()
+            .ok()
+            .filter(|&(width, height)| paging && width > 0 && height > 1)
+            .map(|(width, height)| (width as usize, height as usize));
+        Self {
+            g,
+            out,
+            session,
+            size,
+            output: Vec::new(),
+            shown: 0,
+            row: 0,
+            col: 0,
+        }
+    }
+
+    /// Whether output is held back for the pager.
+    fn holding(&self) -> bool {
+        self.shown < self.output.len()
+    }
+
+    /// Moves the end of the output on screen past `buf`.
+    fn advance(&mut self, buf: &[u8], width: usize) {
+        for c in String::from_utf8_lossy(buf).chars() {
+            if c == '\n' {
+                self.row += 1;
+                self.col = 0;
+            } else if !c.is_control() {
+                self.col += 1;
+                if self.col >= width {
+                    self.row += 1;
+                    self.col = 0;
+                }
+            }
+        }
+    }
+
+    /// The output held back for the pager, if it overflowed the screen.
+    pub fn into_held(self) -> Option<Held> {
+        self.holding().then_some(Held {
+            output: self.output,
+            shown: self.shown,
+        })
+    }
+}
+
+impl Write for AutoPager<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.g.session() != self.session && self.size.take().is_some() {
+            // show what was held back, as the screen now belongs to another
+            self.out.write_all(&self.output[self.shown..])?;
+            self.output.clear();
+            self.shown = 0;
+        }
+        let Some((width, height)) = self.size else {
+            return self.out.write(buf);
+        };
+
+        let holding = self.holding();
+        self.output.extend_from_slice(buf);
+        if !holding {
+            self.advance(buf, width);
+            // leave a row for the prompt
+            if self.row + 1 < height {
+                self.out.write_all(buf)?;
+                self.shown = self.output.len();
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// The pager to use, from `PAGER`, or `None` if paging is off.
+fn pager(g: &Game) -> Option<String> {
+    let computer = g.current_computer();
+    let pager = computer.env.borrow().get("PAGER").cloned();
+    let pager = pager.unwrap_or_else(|| DEFAULT_PAGER.into());
+    (!pager.is_empty()).then_some(pager)
+}
+
+/// Output that overflowed the screen, see [`AutoPager::into_held`].
+pub struct Held {
+    output: Vec<u8>,
+    /// How much of `output` was shown before it was held back.
+    shown: usize,
+}
+
+impl Held {
+    /// Shows the whole output in the pager, or prints what was held back if
+    /// the pager cannot run.
+    pub fn page(self, g: &Game, io: &mut Stdio) -> std::io::Result<()> {
+        let rest = &self.output[self.shown..];
+        let Some(pager) = pager(g) else {
+            return io.stdout.write_all(rest);
+        };
+        let res = g.start_exe_from_path(
+            &pager,
+            Vec::new(),
+            &mut Stdio {
+                stdin: &mut &self.output[..],
+                stdout: &mut *io.stdout,
+                stderr: &mut *io.stderr,
+            },
+        );
+        match res {
+            Ok(res) => res.map(|_| ()),
+            Err(e) => {
+                writeln!(io.stderr, "{e}: \"{pager}\"")?;
+                io.stdout.write_all(rest)
+            }
+        }
+    }
+}
@@ -0,0 +1,652 @@
+//! Splitting command lines into lists of pipelines, pipelines into words
+//! and operators, and grouping those into commands.
+
+use crate::g::glob;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `|`
+    Pipe,
+    /// `>`
+    Write,
+    /// `>>`
+    Append,
+    /// `<`
+    Read,
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Operator::Pipe => "|",
+            Operator::Write => ">",
+            Operator::Append => ">>",
+            Operator::Read => "<",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Word(String),
+    /// A word with unquoted wildcards, as a pattern for [`glob`].
+    Glob(String),
+    Op(Operator),
+}
+
+impl Token {
+    /// The text of a word as typed, without expanding wildcards.
+    pub fn into_word(self) -> Option<String> {
+        match self {
+            Token::Word(word) => Some(word),
+            Token::Glob(pattern) => Some(glob::unescape(&pattern)),
+            Token::Op(_) => None,
+        }
+    }
+}
+
+/// The word being read by [`tokenize`], kept both as plain text and as a
+/// pattern in which only unquoted wildcards are active.
+#[derive(Default)]
+struct WordBuf {
+    word: String,
+    pattern: String,
+    is_glob: bool,
+}
+
+impl WordBuf {
+    /// Adds a quoted or escaped character.
+    fn push_literal(&mut self, ch: char) {
+        self.word.push(ch);
+        glob::push_literal(&mut self.pattern, ch);
+    }
+
+    /// Adds an unquoted character, which may be a wildcard.
+    fn push(&mut self, ch: char) {
+        if "*?[".contains(ch) {
+            self.is_glob = true;
+            self.pattern.push(ch);
+        } else {
+            glob::push_literal(&mut self.pattern, ch);
+        }
+        self.word.push(ch);
+    }
+
+    fn take(&mut self) -> Option<Token> {
+        let buf = std::mem::take(self);
+        match buf {
+            _ if buf.word.is_empty() => None,
+            WordBuf {
+                pattern,
+                is_glob: true,
+                ..
+            } => Some(Token::Glob(pattern)),
+            WordBuf { word, .. } => Some(Token::Word(word)),
+        }
+    }
+}
+
+fn is_var_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_'
+}
+
+/// Reads a variable reference following a `$`: `NAME`, `{NAME}`, a single
+/// digit, or one of `?`, `@` and `#`. Returns `None` (consuming nothing) when
+/// the `$` should be literal.
+fn read_var_name<I: Iterator<Item = char>>(chars: &mut std::iter::Peekable<I>) -> Option<String> {
+    match chars.peek() {
+        Some(&ch) if ch.is_ascii_digit() || "?@#".contains(ch) => {
+            chars.next();
+            Some(ch.to_string())
+        }
+        Some('{') => {
+            chars.next();
+            let mut name = String::new();
+            for ch in chars.by_ref() {
+                if ch == '}' {
+                    break;
+                }
+                name.push(ch);
+            }
+            Some(name)
+        }
+        Some(&ch) if ch.is_ascii_alphabetic() || ch == '_' => {
+            let mut name = String::new();
+            while let Some(&ch) = chars.peek() {
+                if !is_var_char(ch) {
+                    break;
+                }
+                name.push(ch);
+                chars.next();
+            }
+            Some(name)
+        }
+        _ => None,
+    }
+}
+
+/// Splits a command line into words and operators, expanding `$NAME` and
+/// `${NAME}` through `lookup` everywhere except inside single quotes.
+/// Unquoted expansions are split into several words on whitespace.
+/// Operators and wildcards are only recognized outside of quotes and when
+/// not escaped; words with wildcards become [`Token::Glob`].
+pub fn tokenize<T, F>(command: T, lookup: F) -> Vec<Token>
+where
+    T: IntoIterator<Item = char>,
+    F: Fn(&str) -> Option<String>,
+{
+    let mut quote: Option<char> = None;
+    let mut escaping = false;
+
+    let mut tokens = Vec::<Token>::new();
+    let mut cur_arg = WordBuf::default();
+
+    let mut chars = command.into_iter().peekable();
+    while let Some(ch) = chars.next() {
+        if escaping {
+            cur_arg.push_literal(ch);
+            escaping = false;
+        } else if quote == Some('\'') {
+            match ch {
+                '\'' => quote = None,
+                ch => cur_arg.push_literal(ch),
+            }
+        } else {
+            let op = match ch {
+                '|' if quote.is_none() => Some(Operator::Pipe),
+                '<' if quote.is_none() => Some(Operator::Read),
+                '>' if quote.is_none() => {
+                    if chars.next_if_eq(&'>').is_some() {
+                        Some(Operator::Append)
+                    } else {
+                        Some(Operator::Write)
+                    }
+                }
+                _ => None,
+            };
+            if let Some(op) = op {
+                tokens.extend(cur_arg.take());
+                tokens.push(Token::Op(op));
+                continue;
+            }
+
+            match ch {
+                '\\' => escaping = true,
+                '"' if quote == Some('"') => quote = None,
+                '"' | '\'' if quote.is_none() => quote = Some(ch),
+                '$' => match read_var_name(&mut chars) {
+                    // "$@" still splits, so that each argument is its own word
+                    Some(name) if quote.is_some() && name != "@" => {
+                        for ch in lookup(&name).unwrap_or_default().chars() {
+                            cur_arg.push_literal(ch);
+                        }
+                    }
+                    Some(name) => {
+                        for ch in lookup(&name).unwrap_or_default().chars() {
+                            if !ch.is_whitespace() {
+                                cur_arg.push(ch);
+                            } else {
+                                tokens.extend(cur_arg.take());
+                            }
+                        }
+                    }
+                    None => cur_arg.push_literal('$'),
+                },
+                ch if ch.is_whitespace() && quote.is_none() => tokens.extend(cur_arg.take()),
+                ch if quote.is_some() => cur_arg.push_literal(ch),
+                ch => cur_arg.push(ch),
+            };
+        }
+    }
+
+    tokens.extend(cur_arg.take());
+
+    tokens
+}
+
+/// One stage of a pipeline.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Command {
+    pub args: Vec<String>,
+    /// File to read stdin from (`< file`).
+    pub stdin: Option<String>,
+    /// File to write stdout to, and whether to append (`>> file`).
+    pub stdout: Option<(String, bool)>,
+}
+
+fn unexpected(token: Option<&Token>) -> String {
+    match token {
+        Some(Token::Op(op)) => format!("syntax error near unexpected token \"{op}\""),
+        _ => "syntax error near unexpected end of line".to_string(),
+    }
+}
+
+/// Groups tokens into the commands of a pipeline.
+pub fn parse_pipeline(tokens: Vec<Token>) -> Result<Vec<Command>, String> {
+    let mut pipeline = Vec::new();
+    let mut command = Command::default();
+
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Word(word) => command.args.push(word),
+            Token::Glob(pattern) => command.args.push(glob::unescape(&pattern)),
+            Token::Op(Operator::Pipe) => {
+                if command.args.is_empty() {
+                    return Err(unexpected(Some(&Token::Op(Operator::Pipe))));
+                }
+                pipeline.push(std::mem::take(&mut command));
+            }
+            Token::Op(op) => {
+                let file = match tokens.next() {
+                    Some(Token::Word(file)) => file,
+                    Some(Token::Glob(pattern)) => glob::unescape(&pattern),
+                    token => return Err(unexpected(token.as_ref())),
+                };
+                match op {
+                    Operator::Read => command.stdin = Some(file),
+                    Operator::Append => command.stdout = Some((file, true)),
+                    _ => command.stdout = Some((file, false)),
+                }
+            }
+        }
+    }
+
+    if command.args.is_empty() {
+        // a redirection needs a command, as does the end of a pipe
+        if !pipeline.is_empty() || command != Command::default() {
+            return Err(unexpected(None));
+        }
+    } else {
+        pipeline.push(command);
+    }
+
+    Ok(pipeline)
+}
+
+/// When a pipeline in a list runs, based on the status of the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    /// First in the list, or after `;`.
+    Always,
+    /// After `&&`.
+    IfSuccess,
+    /// After `||`.
+    IfFailure,
+}
+
+/// The source of one pipeline in a list, and how it runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    pub condition: Condition,
+    pub source: String,
+    /// Ended with `&`, to run as a background job.
+    pub background: bool,
+}
+
+/// Splits a command line (or a whole script) on unquoted newlines, `;`, `&`,
+/// `&&` and `||` into the source of each pipeline, with the condition under
+/// which it runs. Blank lines and `#` comments are skipped.
+///
+/// Pipelines are kept as text so that variables like `$?` are only expanded
+/// right before each one runs.
+pub fn split_list(line: &str) -> Result<Vec<ListItem>, String> {
+    let mut list = Vec::new();
+    let mut condition = Condition::Always;
+    let mut segment = String::new();
+
+    let mut quote: Option<char> = None;
+    let mut escaping = false;
+
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let at_word_start = segment.chars().last().is_none_or(char::is_whitespace);
+        let next_condition = match ch {
+            _ if escaping || quote.is_some() => None,
+            '#' if at_word_start => {
+                while chars.next_if(|&ch| ch != '\n').is_some() {}
+                continue;
+            }
+            '\n' if segment.trim().is_empty() => {
+                // blank line, or a line ending in an operator
+                segment.clear();
+                continue;
+            }
+            '\n' => Some((Condition::Always, "newline")),
+            ';' => Some((Condition::Always, ";")),
+            '&' if chars.next_if_eq(&'&').is_some() => Some((Condition::IfSuccess, "&&")),
+            '&' => Some((Condition::Always, "&")),
+            '|' if chars.next_if_eq(&'|').is_some() => Some((Condition::IfFailure, "||")),
+            _ => None,
+        };
+
+        if let Some((next_condition, op)) = next_condition {
+            if segment.trim().is_empty() {
+                return Err(format!("syntax error near unexpected token \"{op}\""));
+            }
+            list.push(ListItem {
+                condition,
+                source: std::mem::take(&mut segment),
+                background: op == "&",
+            });
+            condition = next_condition;
+            continue;
+        }
+
+        segment.push(ch);
+        if escaping {
+            escaping = false;
+        } else if quote == Some('\'') {
+            if ch == '\'' {
+                quote = None;
+            }
+        } else if ch == '\\' {
+            escaping = true;
+        } else if quote == Some(ch) {
+            quote = None;
+        } else if quote.is_none() && (ch == '"' || ch == '\'') {
+            quote = Some(ch);
+        }
+    }
+
+    if !segment.trim().is_empty() {
+        list.push(ListItem {
+            condition,
+            source: segment,
+            background: false,
+        });
+    } else if condition != Condition::Always {
+        return Err(unexpected(None));
+    }
+
+    Ok(list)
+}
+
+/// Expands `!!` (the last command), `!n` (command `n`, counting from 1) and
+/// `!-n` (the `n`th last command) outside of single quotes.
+/// Returns `None` if there was nothing to expand.
+pub fn expand_history(line: &str, history: &[String]) -> Result<Option<String>, String> {
+    let mut expanded = String::new();
+    let mut changed = false;
+
+    let mut quote: Option<char> = None;
+    let mut escaping = false;
+
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if escaping || quote == Some('\'') || ch != '!' {
+            if escaping {
+                escaping = false;
+            } else if ch == '\\' && quote != Some('\'') {
+                escaping = true;
+            } else if quote == Some(ch) {
+                quote = None;
+            } else if quote.is_none() && (ch == '"' || ch == '\'') {
+                quote = Some(ch);
+            }
+            expanded.push(ch);
+            continue;
+        }
+
+        let event = if chars.next_if_eq(&'!').is_some() {
+            "!".to_string()
+        } else {
+            let mut event = chars.next_if_eq(&'-').map(String::from).unwrap_or_default();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                event.push(digit);
+            }
+            event
+        };
+        let entry = match event.as_str() {
+            // a lone `!`, as in `[ ! -e file ]`
+            "" => {
+                expanded.push('!');
+                continue;
+            }
+            "-" => return Err("!-: event not found".to_string()),
+            "!" => history.last(),
+            n => match n.strip_prefix('-') {
+                Some(back) => back
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|back| history.len().checked_sub(back))
+                    .and_then(|i| history.get(i)),
+                None => n
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|i| history.get(i)),
+            },
+        };
+        match entry {
+            Some(entry) => expanded.push_str(entry),
+            None => return Err(format!("!{event}: event not found")),
+        }
+        changed = true;
+    }
+
+    Ok(changed.then_some(expanded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> Vec<String> {
+        ["ls", "cd /bin", "echo hi"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn history_events() {
+        let history = history();
+        let expand = |line| expand_history(line, &history);
+        assert_eq!(expand("!!"), Ok(Some("echo hi".to_string())));
+        assert_eq!(expand("sudo !!"), Ok(Some("sudo echo hi".to_string())));
+        assert_eq!(expand("!1"), Ok(Some("ls".to_string())));
+        assert_eq!(
+            expand("!2 && !3"),
+            Ok(Some("cd /bin && echo hi".to_string()))
+        );
+        assert_eq!(expand("!-1"), Ok(Some("echo hi".to_string())));
+        assert_eq!(expand("!-3x"), Ok(Some("lsx".to_string())));
+    }
+
+    #[test]
+    fn history_left_alone() {
+        let history = history();
+        let expand = |line| expand_history(line, &history);
+        assert_eq!(expand("ls"), Ok(None));
+        assert_eq!(expand("[ ! -e file ]"), Ok(None));
+        assert_eq!(expand("echo hi!"), Ok(None));
+        assert_eq!(expand("echo '!!'"), Ok(None));
+        assert_eq!(expand("echo \\!!"), Ok(None));
+        assert_eq!(
+            expand("echo \"!!\""),
+            Ok(Some("echo \"echo hi\"".to_string()))
+        );
+    }
+
+    #[test]
+    fn history_out_of_range() {
+        let history = history();
+        let expand = |line| expand_history(line, &history);
+        assert_eq!(expand("!0"), Err("!0: event not found".to_string()));
+        assert_eq!(expand("!4"), Err("!4: event not found".to_string()));
+        assert_eq!(expand("!-4"), Err("!-4: event not found".to_string()));
+        assert_eq!(expand("!-0"), Err("!-0: event not found".to_string()));
+        assert_eq!(expand("!-"), Err("!-: event not found".to_string()));
+        assert_eq!(
+            expand_history("!!", &[]),
+            Err("!!: event not found".to_string())
+        );
+    }
+
+    fn words(line: &str) -> Vec<Token> {
+        tokenize(line.chars(), |name| match name {
+            "HOME" => Some("/home/user".to_string()),
+            "LIST" => Some("a  b".to_string()),
+            _ => None,
+        })
+    }
+
+    fn word(word: &str) -> Token {
+        Token::Word(word.to_string())
+    }
+
+    #[test]
+    fn tokenize_quotes_and_escapes() {
+        assert_eq!(words("  echo  hi "), [word("echo"), word("hi")]);
+        assert_eq!(
+            words("echo 'a b' \"c d\""),
+            [word("echo"), word("a b"), word("c d")]
+        );
+        assert_eq!(words("echo a\\ b"), [word("echo"), word("a b")]);
+        assert_eq!(words("echo 'it'\\''s'"), [word("echo"), word("it's")]);
+        assert_eq!(
+            words("echo \"'\" '\"'"),
+            [word("echo"), word("'"), word("\"")]
+        );
+        assert_eq!(words("echo ''"), [word("echo")]);
+    }
+
+    #[test]
+    fn tokenize_variables() {
+        assert_eq!(words("cd $HOME/bin"), [word("cd"), word("/home/user/bin")]);
+        assert_eq!(words("echo ${HOME}x"), [word("echo"), word("/home/userx")]);
+        assert_eq!(words("echo '$HOME'"), [word("echo"), word("$HOME")]);
+        assert_eq!(words("echo $NOPE $"), [word("echo"), word("$")]);
+        assert_eq!(words("echo $LIST"), [word("echo"), word("a"), word("b")]);
+        assert_eq!(words("echo \"$LIST\""), [word("echo"), word("a  b")]);
+    }
+
+    #[test]
+    fn tokenize_operators_and_globs() {
+        assert_eq!(
+            words("cat<in|grep x>>out>err"),
+            [
+                word("cat"),
+                Token::Op(Operator::Read),
+                word("in"),
+                Token::Op(Operator::Pipe),
+                word("grep"),
+                word("x"),
+                Token::Op(Operator::Append),
+                word("out"),
+                Token::Op(Operator::Write),
+                word("err"),
+            ]
+        );
+        assert_eq!(words("echo '|' \\>"), [word("echo"), word("|"), word(">")]);
+        assert_eq!(
+            words("ls *.log '*'x"),
+            [word("ls"), Token::Glob("*.log".to_string()), word("*x")]
+        );
+        assert_eq!(
+            words("ls a'*'*"),
+            [word("ls"), Token::Glob("a\\**".to_string())]
+        );
+    }
+
+    fn pipeline(line: &str) -> Result<Vec<Command>, String> {
+        parse_pipeline(words(line))
+    }
+
+    fn command(args: &[&str]) -> Command {
+        Command {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pipelines() {
+        assert_eq!(pipeline(""), Ok(vec![]));
+        assert_eq!(
+            pipeline("ls -l | grep x"),
+            Ok(vec![command(&["ls", "-l"]), command(&["grep", "x"])])
+        );
+        assert_eq!(
+            pipeline("sort < in > out"),
+            Ok(vec![Command {
+                stdin: Some("in".to_string()),
+                stdout: Some(("out".to_string(), false)),
+                ..command(&["sort"])
+            }])
+        );
+        assert_eq!(
+            pipeline("> out echo *x"),
+            Ok(vec![Command {
+                stdout: Some(("out".to_string(), false)),
+                ..command(&["echo", "*x"])
+            }])
+        );
+        assert_eq!(
+            pipeline("echo hi >> log"),
+            Ok(vec![Command {
+                stdout: Some(("log".to_string(), true)),
+                ..command(&["echo", "hi"])
+            }])
+        );
+    }
+
+    #[test]
+    fn pipeline_errors() {
+        let near = |op: &str| Err(format!("syntax error near unexpected token \"{op}\""));
+        let end = Err("syntax error near unexpected end of line".to_string());
+        assert_eq!(pipeline("| ls"), near("|"));
+        assert_eq!(pipeline("ls | | wc"), near("|"));
+        assert_eq!(pipeline("ls |"), end);
+        assert_eq!(pipeline("ls >"), end);
+        assert_eq!(pipeline("ls > | wc"), near("|"));
+        assert_eq!(pipeline("> file"), end);
+        assert_eq!(pipeline("< file"), end);
+    }
+
+    fn item(condition: Condition, source: &str, background: bool) -> ListItem {
+        ListItem {
+            condition,
+            source: source.to_string(),
+            background,
+        }
+    }
+
+    #[test]
+    fn lists() {
+        use Condition::*;
+        assert_eq!(split_list(""), Ok(vec![]));
+        assert_eq!(
+            split_list("a; b && c || d & e"),
+            Ok(vec![
+                item(Always, "a", false),
+                item(Always, " b ", false),
+                item(IfSuccess, " c ", false),
+                item(IfFailure, " d ", true),
+                item(Always, " e", false),
+            ])
+        );
+        assert_eq!(
+            split_list("a &&\n\n# comment\nb # more\n\nc | d"),
+            Ok(vec![
+                item(Always, "a ", false),
+                item(IfSuccess, "b ", false),
+                item(Always, "c | d", false),
+            ])
+        );
+        assert_eq!(
+            split_list("echo 'a;b' \"&&\" \\; x#y"),
+            Ok(vec![item(Always, "echo 'a;b' \"&&\" \\; x#y", false)])
+        );
+    }
+
+    #[test]
+    fn list_errors() {
+        let near = |op: &str| Err(format!("syntax error near unexpected token \"{op}\""));
+        assert_eq!(split_list("; ls"), near(";"));
+        assert_eq!(split_list("ls &&&& wc"), near("&&"));
+        assert_eq!(split_list("ls ;; wc"), near(";"));
+        assert_eq!(
+            split_list("ls ||"),
+            Err("syntax error near unexpected end of line".to_string())
+        );
+    }
+}
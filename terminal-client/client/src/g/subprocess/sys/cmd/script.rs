@@ -0,0 +1,152 @@
+//! Grouping the pipelines of a script into `if` and `for` blocks.
+
+use std::collections::VecDeque;
+
+use super::parse::{parse_pipeline, split_list, tokenize, Condition, ListItem};
+
+pub const KEYWORDS: &[&str] = &["if", "then", "elif", "else", "fi", "for", "do", "done"];
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    /// The source of a pipeline, expanded right before it runs, and whether
+    /// to run it as a background job.
+    Pipeline { source: String, background: bool },
+    /// `if cond; then body; elif cond; then body; else otherwise; fi`
+    If {
+        branches: Vec<(Block, Block)>,
+        otherwise: Block,
+    },
+    /// `for var in words; do body; done`
+    For {
+        var: String,
+        words: String,
+        body: Block,
+    },
+}
+
+/// Statements paired with the condition under which they run.
+pub type Block = Vec<(Condition, Stmt)>;
+
+struct Parser {
+    pieces: VecDeque<ListItem>,
+}
+
+impl Parser {
+    /// Splits off the keyword the front piece starts with, if any, leaving
+    /// the rest of the piece in its place.
+    fn take_keyword(&mut self) -> Option<&'static str> {
+        let item = self.pieces.front()?;
+        let background = item.background;
+        let text = item.source.trim_start();
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        let keyword = KEYWORDS.iter().find(|k| **k == &text[..end])?;
+
+        let rest = text[end..].to_string();
+        self.pieces.pop_front();
+        if !rest.trim().is_empty() {
+            self.pieces.push_front(ListItem {
+                condition: Condition::Always,
+                source: rest,
+                background,
+            });
+        }
+        Some(keyword)
+    }
+
+    /// Parses statements until one of `terminators`, which is consumed and
+    /// returned. With no terminators, parses until the end.
+    fn block(&mut self, terminators: &[&str]) -> Result<(Block, &'static str), String> {
+        let mut block = Block::new();
+        loop {
+            let Some(condition) = self.pieces.front().map(|item| item.condition) else {
+                return match terminators {
+                    [] => Ok((block, "")),
+                    _ => Err("syntax error: unexpected end of file".into()),
+                };
+            };
+
+            match self.take_keyword() {
+                Some(keyword) if terminators.contains(&keyword) => return Ok((block, keyword)),
+                Some("if") => block.push((condition, self.if_stmt()?)),
+                Some("for") => block.push((condition, self.for_stmt()?)),
+                Some(keyword) => {
+                    return Err(format!("syntax error near unexpected token \"{keyword}\""))
+                }
+                None => {
+                    let ListItem {
+                        source, background, ..
+                    } = self.pieces.pop_front().unwrap();
+                    parse_pipeline(tokenize(source.chars(), |_| None))?;
+                    block.push((condition, Stmt::Pipeline { source, background }));
+                }
+            }
+        }
+    }
+
+    fn if_stmt(&mut self) -> Result<Stmt, String> {
+        let mut branches = Vec::new();
+        loop {
+            let (cond, _) = self.block(&["then"])?;
+            let (body, end) = self.block(&["elif", "else", "fi"])?;
+            branches.push((cond, body));
+            match end {
+                "elif" => continue,
+                "else" => {
+                    let (otherwise, _) = self.block(&["fi"])?;
+                    return Ok(Stmt::If {
+                        branches,
+                        otherwise,
+                    });
+                }
+                _ => {
+                    return Ok(Stmt::If {
+                        branches,
+                        otherwise: Block::new(),
+                    })
+                }
+            }
+        }
+    }
+
+    fn for_stmt(&mut self) -> Result<Stmt, String> {
+        let header = self
+            .pieces
+            .pop_front()
+            .map(|item| item.source)
+            .unwrap_or_default();
+        let header = header.trim();
+        let (var, words) = match header.split_once(char::is_whitespace) {
+            Some((var, rest)) => match rest.trim_start().strip_prefix("in") {
+                Some(words) if words.is_empty() || words.starts_with(char::is_whitespace) => {
+                    (var, words.to_string())
+                }
+                _ => return Err("syntax error: expected \"in\" after for".into()),
+            },
+            // `for var` iterates over the arguments
+            None => (header, "$@".to_string()),
+        };
+        if var.is_empty() {
+            return Err("syntax error: expected a variable name after for".into());
+        }
+
+        let (before_do, _) = self.block(&["do"])?;
+        if !before_do.is_empty() {
+            return Err("syntax error: expected \"do\"".into());
+        }
+        let (body, _) = self.block(&["done"])?;
+
+        Ok(Stmt::For {
+            var: var.to_string(),
+            words,
+            body,
+        })
+    }
+}
+
+/// Parses a command line or a whole script.
+pub fn parse_script(source: &str) -> Result<Block, String> {
+    let mut parser = Parser {
+        pieces: split_list(source)?.into(),
+    };
+    parser.block(&[]).map(|(block, _)| block)
+}
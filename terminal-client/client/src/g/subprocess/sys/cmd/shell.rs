@@ -0,0 +1,565 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    rc::Rc,
+};
+
+use crate::{
+    g::{
+        computer::RC_FILE,
+        fs::{FsError, Path},
+        process::Pid,
+        subprocess::{ExitStatus, Stdio},
+        Game,
+    },
+    path,
+};
+
+use super::{
+    parse::{parse_pipeline, tokenize, Command, Condition, Operator, Token},
+    script::{parse_script, Block, Stmt},
+};
+
+/// Commands run by the shell itself rather than looked up on the path.
+pub const BUILTINS: &[&str] = &["source", ".", "alias", "unalias", "exit", "jobs", "fg"];
+
+/// Aliases by name, shared with the line editor so that it knows them.
+pub type Aliases = Rc<RefCell<BTreeMap<String, String>>>;
+
+/// A pipeline started with `&`. Subprocesses cannot run alongside the
+/// terminal, so it runs to the end as soon as it is started, listed in the
+/// process table meanwhile, and what it printed is kept until the next prompt.
+struct Job {
+    id: usize,
+    pid: Pid,
+    source: String,
+    status: ExitStatus,
+    output: Vec<u8>,
+}
+
+impl Job {
+    /// How the job ended, as `jobs` shows it.
+    fn state(&self) -> String {
+        match self.status {
+            ExitStatus::SUCCESS => "Done".to_string(),
+            ExitStatus::KILLED => "Killed".to_string(),
+            status => format!("Exit {status}"),
+        }
+    }
+}
+
+/// The state of one running `cmd`, interactive or running a script.
+pub struct Shell<'a> {
+    g: &'a Game,
+    /// The status of the last command, `$?`.
+    pub status: ExitStatus,
+    /// `$0`, `$1`, ...
+    args: Vec<String>,
+    /// Variables local to this shell, like `for` loop variables.
+    vars: HashMap<String, String>,
+    /// Set with `alias name=value`.
+    aliases: Aliases,
+    jobs: Vec<Job>,
+    /// Set by `exit`, after which nothing else runs.
+    pub exit: Option<ExitStatus>,
+}
+
+impl<'a> Shell<'a> {
+    pub fn new(g: &'a Game, args: Vec<String>) -> Self {
+        Self {
+            g,
+            status: ExitStatus::SUCCESS,
+            args,
+            vars: HashMap::new(),
+            aliases: Aliases::default(),
+            jobs: Vec::new(),
+            exit: None,
+        }
+    }
+
+    /// The aliases of this shell, which change as it runs `alias`.
+    pub fn aliases(&self) -> Aliases {
+        self.aliases.clone()
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        match name {
+            "?" => Some(self.status.to_string()),
+            "#" => Some(self.args.len().saturating_sub(1).to_string()),
+            "@" => Some(self.args.get(1..).unwrap_or_default().join(" ")),
+            name if name.chars().all(|c| c.is_ascii_digit()) => name
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| self.args.get(i).cloned()),
+            name => self.vars.get(name).cloned().or_else(|| {
+                let computer = self.g.current_computer();
+                let value = computer.env.borrow().get(name).cloned();
+                value
+            }),
+        }
+    }
+
+    /// Parses and runs a command line or a whole script.
+    pub fn run_source(&mut self, source: &str, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        match parse_script(source) {
+            Ok(block) => self.run_block(&block, io)?,
+            Err(e) => {
+                writeln!(io.stderr, "{e}")?;
+                self.status = ExitStatus::USAGE;
+            }
+        }
+        Ok(self.status)
+    }
+
+    /// Whether to stop running commands, after `exit` or Ctrl-C.
+    fn stopped(&self) -> bool {
+        self.exit.is_some() || self.g.interrupted()
+    }
+
+    fn run_block(&mut self, block: &Block, io: &mut Stdio) -> std::io::Result<()> {
+        for (condition, stmt) in block {
+            if self.stopped() {
+                break;
+            }
+            let run = match condition {
+                Condition::Always => true,
+                Condition::IfSuccess => self.status.success(),
+                Condition::IfFailure => !self.status.success(),
+            };
+            if run {
+                self.run_stmt(stmt, io)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn run_stmt(&mut self, stmt: &Stmt, io: &mut Stdio) -> std::io::Result<()> {
+        match stmt {
+            Stmt::Pipeline {
+                source,
+                background: true,
+            } => self.status = self.spawn_job(source, io)?,
+            Stmt::Pipeline { source, .. } => {
+                let tokens = tokenize(source.chars(), |name| self.lookup(name));
+                let tokens = self.expand_globs(self.expand_aliases(tokens));
+                match parse_pipeline(tokens) {
+                    Ok(pipeline) => {
+                        self.status = self.run_pipeline(&pipeline, io)?;
+                        io.stdout.flush()?;
+                    }
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}")?;
+                        self.status = ExitStatus::USAGE;
+                    }
+                }
+            }
+            Stmt::If {
+                branches,
+                otherwise,
+            } => {
+                for (cond, body) in branches {
+                    self.run_block(cond, io)?;
+                    if self.stopped() {
+                        return Ok(());
+                    }
+                    if self.status.success() {
+                        return self.run_block(body, io);
+                    }
+                }
+                self.status = ExitStatus::SUCCESS;
+                self.run_block(otherwise, io)?;
+            }
+            Stmt::For { var, words, body } => {
+                let words = self
+                    .expand_globs(tokenize(words.chars(), |name| self.lookup(name)))
+                    .into_iter()
+                    .filter_map(Token::into_word)
+                    .collect::<Vec<_>>();
+
+                self.status = ExitStatus::SUCCESS;
+                for word in words {
+                    self.vars.insert(var.clone(), word);
+                    self.run_block(body, io)?;
+                    if self.stopped() {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the first word of each command of a pipeline by its alias.
+    fn expand_aliases(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let mut expanded = Vec::with_capacity(tokens.len());
+        let mut command_start = true;
+        for token in tokens {
+            match token {
+                Token::Word(word) if command_start => {
+                    expanded.extend(self.expand_alias(word, &mut Vec::new()));
+                    command_start = false;
+                }
+                token => {
+                    command_start = token == Token::Op(Operator::Pipe);
+                    expanded.push(token);
+                }
+            }
+        }
+        expanded
+    }
+
+    /// Expands `word` if it is an alias, and in turn the first word of its
+    /// value, unless that alias is already being expanded (as in `alias ls='ls /'`).
+    fn expand_alias(&self, word: String, seen: &mut Vec<String>) -> Vec<Token> {
+        let value = self.aliases.borrow().get(&word).cloned();
+        let Some(value) = value.filter(|_| !seen.contains(&word)) else {
+            return vec![Token::Word(word)];
+        };
+        seen.push(word);
+        let mut tokens = tokenize(value.chars(), |name| self.lookup(name));
+        if let Some(Token::Word(first)) = tokens.first().cloned() {
+            tokens.splice(..1, self.expand_alias(first, seen));
+        }
+        tokens
+    }
+
+    /// Replaces each wildcard pattern with the paths it matches, or with the
+    /// pattern itself if none do. Redirection targets are left alone.
+    fn expand_globs(&self, tokens: Vec<Token>) -> Vec<Token> {
+        let computer = self.g.current_computer();
+        let mut expanded = Vec::with_capacity(tokens.len());
+        let mut after_redirect = false;
+        for token in tokens {
+            match token {
+                Token::Glob(pattern) if !after_redirect => {
+                    let matches = computer.glob(&pattern);
+                    if matches.is_empty() {
+                        expanded.push(Token::Glob(pattern));
+                    } else {
+                        expanded.extend(matches.into_iter().map(Token::Word));
+                    }
+                }
+                token => {
+                    after_redirect = matches!(token, Token::Op(op) if op != Operator::Pipe);
+                    expanded.push(token);
+                }
+            }
+        }
+        expanded
+    }
+
+    /// Runs `args[0]` as a builtin, or from the path with the rest as arguments.
+    fn run_command(&mut self, args: &[String], io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        let proc_name = &args[0];
+        match proc_name.as_str() {
+            "source" | "." => return self.source(&args[1..], io),
+            "alias" => return self.alias(&args[1..], io),
+            "unalias" => return self.unalias(&args[1..], io),
+            "jobs" => return self.list_jobs(io),
+            "fg" => return self.fg(&args[1..], io),
+            "exit" => {
+                let status = match args.get(1).map(|code| code.parse()) {
+                    Some(Ok(code)) => ExitStatus(code),
+                    Some(Err(_)) => {
+                        writeln!(io.stderr, "exit: numeric argument required")?;
+                        ExitStatus::USAGE
+                    }
+                    None => self.status,
+                };
+                self.exit = Some(status);
+                return Ok(status);
+            }
+            _ => {}
+        }
+
+        Ok(
+            match self.g.start_exe_from_path(proc_name, &args[1..], io) {
+                Ok(Ok(status)) => status,
+                Ok(Err(e)) => {
+                    writeln!(io.stderr, "{proc_name}: {e}")?;
+                    ExitStatus::FAILURE
+                }
+                Err(FsError::NotExecutable) => {
+                    writeln!(
+                        io.stderr,
+                        "Could not find process \"{}\"\nType \"help\" to list all processes.",
+                        proc_name
+                    )?;
+                    ExitStatus::NOT_FOUND
+                }
+                Err(e) => {
+                    writeln!(io.stderr, "{proc_name}: {e}")?;
+                    ExitStatus::NOT_EXECUTABLE
+                }
+            },
+        )
+    }
+
+    /// `alias [name[=value]...]`: defines aliases, or prints them.
+    fn alias(&mut self, args: &[String], io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        if args.is_empty() {
+            for (name, value) in self.aliases.borrow().iter() {
+                writeln!(io.stdout, "alias {name}='{value}'")?;
+            }
+            return Ok(ExitStatus::SUCCESS);
+        }
+
+        let mut status = ExitStatus::SUCCESS;
+        for arg in args {
+            match arg.split_once('=') {
+                Some((name, _)) if name.is_empty() || name.contains(['/', ' ', '\t']) => {
+                    writeln!(io.stderr, "alias: \"{name}\": invalid alias name")?;
+                    status = ExitStatus::FAILURE;
+                }
+                Some((name, value)) => {
+                    self.aliases.borrow_mut().insert(name.to_string(), value.to_string());
+                }
+                None => match self.aliases.borrow().get(arg) {
+                    Some(value) => writeln!(io.stdout, "alias {arg}='{value}'")?,
+                    None => {
+                        writeln!(io.stderr, "alias: \"{arg}\": not found")?;
+                        status = ExitStatus::FAILURE;
+                    }
+                },
+            }
+        }
+        Ok(status)
+    }
+
+    /// `unalias -a | name...`: removes all aliases, or the given ones.
+    fn unalias(&mut self, args: &[String], io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        if args.is_empty() {
+            writeln!(io.stderr, "unalias: usage: unalias -a | name...")?;
+            return Ok(ExitStatus::USAGE);
+        }
+        if args.iter().any(|arg| arg == "-a") {
+            self.aliases.borrow_mut().clear();
+            return Ok(ExitStatus::SUCCESS);
+        }
+
+        let mut status = ExitStatus::SUCCESS;
+        for name in args {
+            if self.aliases.borrow_mut().remove(name).is_none() {
+                writeln!(io.stderr, "unalias: \"{name}\": not found")?;
+                status = ExitStatus::FAILURE;
+            }
+        }
+        Ok(status)
+    }
+
+    /// Starts `source` as a new background job, in a copy of this shell with
+    /// no input. Its errors are shown at once, its output at the next prompt.
+    fn spawn_job(&mut self, source: &str, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        let computer = self.g.current_computer();
+        let source = source.trim().to_string();
+        let pid = computer.processes.borrow_mut().spawn(
+            &computer.current_user().name,
+            &source,
+            self.g.clock.now(),
+            false,
+        );
+        let id = self.jobs.last().map_or(1, |job| job.id + 1);
+        writeln!(io.stdout, "[{id}] {pid}")?;
+        io.stdout.flush()?;
+
+        let mut shell = Shell {
+            g: self.g,
+            status: self.status,
+            args: self.args.clone(),
+            vars: self.vars.clone(),
+            aliases: Rc::new(RefCell::new(self.aliases.borrow().clone())),
+            jobs: Vec::new(),
+            exit: None,
+        };
+        let mut output = Vec::new();
+        let mut job_io = Stdio {
+            stdin: &mut std::io::empty(),
+            stdout: &mut output,
+            stderr: &mut *io.stderr,
+        };
+        shell.run_source(&source, &mut job_io)?;
+        shell.report_jobs(&mut job_io)?;
+        let status = if computer.processes.borrow_mut().remove(pid).is_some() {
+            shell.exit.unwrap_or(shell.status)
+        } else {
+            ExitStatus::KILLED
+        };
+
+        self.jobs.push(Job {
+            id,
+            pid,
+            source,
+            status,
+            output,
+        });
+        Ok(ExitStatus::SUCCESS)
+    }
+
+    /// Shows what each background job printed and how it ended, then forgets
+    /// it. Run before each prompt.
+    pub fn report_jobs(&mut self, io: &mut Stdio) -> std::io::Result<()> {
+        for job in self.jobs.drain(..) {
+            io.stdout.write_all(&job.output)?;
+            writeln!(io.stdout, "[{}]  {:<10} {}", job.id, job.state(), job.source)?;
+        }
+        Ok(())
+    }
+
+    /// `jobs`: lists background jobs that have not been reported yet.
+    fn list_jobs(&mut self, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        for job in &self.jobs {
+            writeln!(
+                io.stdout,
+                "[{}] {:>5} {:<10} {} &",
+                job.id,
+                job.pid,
+                job.state(),
+                job.source
+            )?;
+        }
+        Ok(ExitStatus::SUCCESS)
+    }
+
+    /// `fg [%job]`: brings a background job, by default the last one, to the
+    /// foreground: shows what it printed, and takes its status.
+    fn fg(&mut self, args: &[String], io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        let index = match args {
+            [] => self.jobs.len().checked_sub(1),
+            [spec] => {
+                let id = spec.strip_prefix('%').unwrap_or(spec).parse::<usize>().ok();
+                self.jobs.iter().position(|job| Some(job.id) == id)
+            }
+            _ => {
+                writeln!(io.stderr, "fg: usage: fg [%job]")?;
+                return Ok(ExitStatus::USAGE);
+            }
+        };
+        let Some(index) = index else {
+            writeln!(io.stderr, "fg: no such job")?;
+            return Ok(ExitStatus::FAILURE);
+        };
+
+        let job = self.jobs.remove(index);
+        writeln!(io.stdout, "{}", job.source)?;
+        io.stdout.write_all(&job.output)?;
+        Ok(job.status)
+    }
+
+    /// Sources the current user's startup file, if they have one.
+    pub fn source_rc(&mut self, io: &mut Stdio) -> std::io::Result<()> {
+        let computer = self.g.current_computer();
+        let rc = computer.home().join(&path![RC_FILE]);
+        if computer.lookup(&rc).is_ok() {
+            self.source(&[rc.to_string()], io)?;
+        }
+        Ok(())
+    }
+
+    /// `source file [args...]`: runs `file` in this shell, so that it can
+    /// change its variables. With args, they replace `$1`, `$2`, ... meanwhile.
+    fn source(&mut self, args: &[String], io: &mut Stdio) -> std::io::Result<ExitStatus> {
+        let Some(file) = args.first() else {
+            writeln!(io.stderr, "source: filename argument required")?;
+            return Ok(ExitStatus::USAGE);
+        };
+
+        let computer = self.g.current_computer();
+        let content = match computer.read_file(&computer.parse_path(file)) {
+            Ok(content) => content,
+            Err(e) => {
+                writeln!(io.stderr, "{e}: \"{file}\"")?;
+                return Ok(ExitStatus::FAILURE);
+            }
+        };
+
+        let saved_args = (args.len() > 1).then(|| {
+            let mut new_args = vec![self.args[0].clone()];
+            new_args.extend_from_slice(&args[1..]);
+            std::mem::replace(&mut self.args, new_args)
+        });
+        let status = self.run_source(&content, io)?;
+        if let Some(saved_args) = saved_args {
+            self.args = saved_args;
+        }
+        Ok(status)
+    }
+
+    /// Runs each command of `pipeline` in turn, feeding the output of one into
+    /// the input of the next, and applying file redirections.
+    /// Returns the status of the last command.
+    fn run_pipeline(
+        &mut self,
+        pipeline: &[Command],
+        io: &mut Stdio,
+    ) -> std::io::Result<ExitStatus> {
+        let computer = self.g.current_computer();
+        let mut status = ExitStatus::SUCCESS;
+        let mut input: Option<Vec<u8>> = None;
+
+        for (i, command) in pipeline.iter().enumerate() {
+            if self.g.interrupted() {
+                return Ok(ExitStatus::INTERRUPTED);
+            }
+            let last = i + 1 == pipeline.len();
+
+            let stdin_buf = match &command.stdin {
+                Some(file) => match computer.read_file(&computer.parse_path(file)) {
+                    Ok(content) => Some(content.into_bytes()),
+                    Err(e) => {
+                        writeln!(io.stderr, "{e}: \"{file}\"")?;
+                        status = ExitStatus::FAILURE;
+                        input = Some(Vec::new());
+                        continue;
+                    }
+                },
+                None => input.take(),
+            };
+
+            let mut output = Vec::<u8>::new();
+            let capture = !last || command.stdout.is_some();
+            {
+                let mut stdin_slice;
+                let stdin: &mut dyn std::io::BufRead = match &stdin_buf {
+                    Some(buf) => {
+                        stdin_slice = &buf[..];
+                        &mut stdin_slice
+                    }
+                    None => &mut *io.stdin,
+                };
+                let stdout: &mut dyn Write = if capture {
+                    &mut output
+                } else {
+                    &mut *io.stdout
+                };
+                status = self.run_command(
+                    &command.args,
+                    &mut Stdio {
+                        stdin,
+                        stdout,
+                        stderr: &mut *io.stderr,
+                    },
+                )?;
+            }
+
+            match &command.stdout {
+                Some((file, append)) => {
+                    let content = String::from_utf8_lossy(&output);
+                    if let Err(e) =
+                        computer.write_file(&computer.parse_path(file), &content, *append)
+                    {
+                        writeln!(io.stderr, "{e}: \"{file}\"")?;
+                        status = ExitStatus::FAILURE;
+                    }
+                    input = Some(Vec::new());
+                }
+                None => input = Some(output),
+            }
+        }
+
+        if self.g.interrupted() {
+            status = ExitStatus::INTERRUPTED;
+        }
+        Ok(status)
+    }
+}
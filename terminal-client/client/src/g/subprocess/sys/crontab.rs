@@ -0,0 +1,134 @@
+use crate::g::{
+    cron::parse_crontab,
+    subprocess::{
+        args::{ArgError, ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "crontab",
+    title: Some("Cron Table"),
+    description: "lists and installs scheduled jobs.",
+    about: concat!(
+        "Installs file as the crontab of user, or of the current user.\n",
+        "Each line is a schedule, minute hour day-of-month month day-of-week,\n",
+        "then a command, e.g. \"*/5 * * * * echo hi\" runs every 5 minutes.\n",
+        "Output of jobs is mailed to /var/mail/user."
+    ),
+    opts: &[
+        Opt::flag('u', "user", "acts on the crontab of user, for root only").with_value("user"),
+        Opt::flag('l', "list", "lists the jobs instead"),
+        Opt::flag('r', "remove", "removes the crontab instead"),
+    ],
+    args: &[Positional::optional("file")],
+};
+
+/// What `crontab` was asked to do.
+enum Action<'a> {
+    List,
+    Remove,
+    Install(&'a str),
+}
+
+pub const CRONTAB: Subprocess = {
+    struct Crontab;
+
+    impl SubprocessFn for Crontab {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let action = match (
+                args.flag("list"),
+                args.flag("remove"),
+                args.positionals.first(),
+            ) {
+                (true, true, _) => {
+                    return ARGS.usage_error(ArgError::ExclusiveOptions("list", "remove"), io)
+                }
+                (true, _, Some(file)) | (_, true, Some(file)) => {
+                    return ARGS.usage_error(ArgError::UnexpectedArgument(file.clone()), io)
+                }
+                (true, false, None) => Action::List,
+                (false, true, None) => Action::Remove,
+                (false, false, Some(file)) => Action::Install(file),
+                (false, false, None) => {
+                    return ARGS.usage_error(ArgError::MissingArgument("file"), io)
+                }
+            };
+
+            let computer = g.current_computer();
+            let current_user = computer.current_user();
+            let user = args.value("user").unwrap_or(&current_user.name);
+            if user != current_user.name && !current_user.is_root() {
+                writeln!(io.stderr, "crontab: must be privileged to use -u")?;
+                return Ok(ExitStatus::FAILURE);
+            }
+            if computer.find_user(user).is_none() {
+                writeln!(io.stderr, "crontab: user \"{user}\" unknown")?;
+                return Ok(ExitStatus::FAILURE);
+            }
+
+            match action {
+                Action::List => {
+                    let system = computer
+                        .cron
+                        .iter()
+                        .filter(|job| job.user == user)
+                        .collect::<Vec<_>>();
+                    let crontab = computer.crontab(user);
+                    if system.is_empty() && crontab.is_none() {
+                        writeln!(io.stderr, "no crontab for {user}")?;
+                        return Ok(ExitStatus::FAILURE);
+                    }
+                    if !system.is_empty() {
+                        writeln!(io.stdout, "# installed by the system")?;
+                        for job in system {
+                            writeln!(io.stdout, "{} {}", job.spec, job.command)?;
+                        }
+                    }
+                    if let Some(crontab) = crontab {
+                        write!(io.stdout, "{crontab}")?;
+                    }
+                }
+                Action::Remove => {
+                    if !computer.set_crontab(user, None) {
+                        writeln!(io.stderr, "no crontab for {user}")?;
+                        return Ok(ExitStatus::FAILURE);
+                    }
+                }
+                Action::Install(file) => {
+                    let content = match computer.read_file(&computer.parse_path(file)) {
+                        Ok(content) => content,
+                        Err(e) => {
+                            writeln!(io.stderr, "crontab: {e}: \"{file}\"")?;
+                            return Ok(ExitStatus::FAILURE);
+                        }
+                    };
+                    let mut valid = true;
+                    for (line, job) in parse_crontab(user, &content) {
+                        if let Err(e) = job {
+                            writeln!(io.stderr, "crontab: line {line}: {e}")?;
+                            valid = false;
+                        }
+                    }
+                    if !valid {
+                        writeln!(io.stderr, "crontab: errors in crontab file, can't install")?;
+                        return Ok(ExitStatus::FAILURE);
+                    }
+                    computer.set_crontab(user, Some(&content));
+                }
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Crontab
+};
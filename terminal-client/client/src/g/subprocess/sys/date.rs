@@ -0,0 +1,60 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgError, ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const DEFAULT_FORMAT: &str = "%a %b %e %H:%M:%S UTC %Y";
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "date",
+    title: None,
+    description: "prints the current date and time.",
+    about: concat!(
+        "Prints the time of this computer's clock, by default as\n",
+        "\"Mon Jan 15 22:00:00 UTC 2024\".\n",
+        "format may use %Y year, %m month, %d day, %H hour, %M minute,\n",
+        "%S second, %a/%b day/month name, %s seconds since 1970, %% a %."
+    ),
+    opts: &[],
+    args: &[Positional::optional("+format")],
+};
+
+pub const DATE: Subprocess = {
+    struct Date;
+
+    impl SubprocessFn for Date {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let format = match args.first() {
+                None => DEFAULT_FORMAT,
+                Some(format) if format.starts_with('+') => &format[1..],
+                Some(format) => {
+                    let e = ArgError::InvalidValue("+format".into(), format.clone());
+                    return ARGS.usage_error(e, io);
+                }
+            };
+
+            // unlike `format`, this does not panic on invalid specifiers
+            let mut out = String::new();
+            let now = g.clock.now();
+            if std::fmt::write(&mut out, format_args!("{}", now.format(format))).is_err() {
+                writeln!(io.stderr, "date: invalid format \"{format}\"")?;
+                return Ok(ExitStatus::FAILURE);
+            }
+            writeln!(io.stdout, "{out}")?;
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Date
+};
@@ -0,0 +1,41 @@
+use crate::g::{
+    subprocess::{ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo},
+    Game,
+};
+
+pub const ECHO: Subprocess = {
+    struct Echo;
+
+    impl SubprocessFn for Echo {
+        fn info(&self) -> SubprocessInfo {
+            SubprocessInfo {
+                name: None,
+                description: Some("prints its arguments.".into()),
+                help_text: Some(
+                    concat!(
+                        "echo [-n] [args...]\n",
+                        "\n",
+                        "-n: do not print a trailing newline",
+                    )
+                    .into(),
+                ),
+            }
+        }
+
+        fn run(&self, _g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let (newline, args) = match args.first().map(String::as_str) {
+                Some("-n") => (false, &args[1..]),
+                _ => (true, &args[..]),
+            };
+
+            write!(io.stdout, "{}", args.join(" "))?;
+            if newline {
+                writeln!(io.stdout)?;
+            }
+            io.stdout.flush()?;
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Echo
+};
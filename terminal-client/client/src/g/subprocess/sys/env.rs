@@ -0,0 +1,40 @@
+use crate::g::{
+    subprocess::{args::ArgSpec, ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo},
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "env",
+    title: None,
+    description: "lists the environment variables.",
+    about: "Lists each variable as name=value.",
+    opts: &[],
+    args: &[],
+};
+
+pub const ENV: Subprocess = {
+    struct Env;
+
+    impl SubprocessFn for Env {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            if let Err(e) = ARGS.parse(&args) {
+                return ARGS.usage_error(e, io);
+            }
+            let computer = g.current_computer();
+            let env = computer.env.borrow();
+            let mut vars = env.iter().collect::<Vec<_>>();
+            vars.sort();
+
+            for (name, value) in vars {
+                writeln!(io.stdout, "{name}={value}")?;
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Env
+};
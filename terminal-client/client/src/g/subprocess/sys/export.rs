@@ -0,0 +1,60 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "export",
+    title: None,
+    description: "sets environment variables.",
+    about: "Without arguments, lists the environment like env.",
+    opts: &[],
+    args: &[Positional::many("name=value")],
+};
+
+pub const EXPORT: Subprocess = {
+    struct Export;
+
+    impl SubprocessFn for Export {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            if args.is_empty() {
+                return g
+                    .start_exe_from_path("env", vec![], io)
+                    .unwrap_or(Ok(ExitStatus::NOT_FOUND));
+            }
+
+            let computer = g.current_computer();
+            let mut status = ExitStatus::SUCCESS;
+            for arg in args {
+                let (name, value) = arg.split_once('=').unwrap_or((&arg, ""));
+                if name.is_empty()
+                    || name.starts_with(|c: char| c.is_ascii_digit())
+                    || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    writeln!(io.stderr, "export: \"{arg}\": not a valid identifier")?;
+                    status = ExitStatus::FAILURE;
+                    continue;
+                }
+
+                let mut env = computer.env.borrow_mut();
+                if arg.contains('=') || !env.contains_key(name) {
+                    env.insert(name.to_string(), value.to_string());
+                }
+            }
+            Ok(status)
+        }
+    }
+
+    &Export
+};
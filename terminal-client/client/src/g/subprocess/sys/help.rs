@@ -2,7 +2,7 @@ use std::collections::BinaryHeap;
 
 use crate::g::{
     fs::NodeContent,
-    subprocess::{Subprocess, SubprocessFn, SubprocessInfo},
+    subprocess::{ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo},
     Game,
 };
 
@@ -24,7 +24,7 @@ pub const HELP: Subprocess = {
             }
         }
 
-        fn run(&self, g: &Game, args: Vec<String>) -> std::io::Result<()> {
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
             if args.len() == 1 {
                 let subprocess_name = args.first().unwrap();
                 match g.current_computer().which_node(subprocess_name) {
@@ -40,42 +40,67 @@ pub const HELP: Subprocess = {
                                 };
 
                                 if let Some(desc) = info.description {
-                                    println!("{name} -- {desc}")
+                                    writeln!(io.stdout, "{name} -- {desc}")?;
                                 } else {
-                                    println!("{name}")
+                                    writeln!(io.stdout, "{name}")?;
                                 }
 
                                 if let Some(help) = info.help_text {
-                                    println!("{help}")
+                                    writeln!(io.stdout, "{help}")?;
                                 } else {
-                                    println!("This executable has no help documentation.");
+                                    writeln!(
+                                        io.stdout,
+                                        "This executable has no help documentation."
+                                    )?;
                                 }
                             }
-                            _ => println!("Could not find executable \"{}\"", subprocess_name),
+                            _ => {
+                                writeln!(
+                                    io.stderr,
+                                    "Could not find executable \"{}\"",
+                                    subprocess_name
+                                )?;
+                                return Ok(ExitStatus::FAILURE);
+                            }
                         }
                     }
-                    None => println!("Could not find executable \"{}\"", subprocess_name),
+                    None => {
+                        writeln!(
+                            io.stderr,
+                            "Could not find executable \"{}\"",
+                            subprocess_name
+                        )?;
+                        return Ok(ExitStatus::FAILURE);
+                    }
                 };
             } else {
-                println!("{}", self.info().help_text.unwrap());
+                writeln!(io.stdout, "{}", self.info().help_text.unwrap())?;
 
-                g.current_computer()
+                for (name, info) in g
+                    .current_computer()
                     .exes()
                     .iter()
                     .map(|node| (node.borrow().name.clone(), node.as_exe().unwrap().info()))
                     .collect::<BinaryHeap<(String, SubprocessInfo)>>()
                     .iter()
                     .rev()
-                    .for_each(|(name, info)| {
-                        if let Some(desc) = info.description.clone() {
-                            println!("{name} -- {desc}");
-                        } else {
-                            println!("{name}");
-                        }
-                    });
+                {
+                    if let Some(desc) = info.description.clone() {
+                        writeln!(io.stdout, "{name} -- {desc}")?;
+                    } else {
+                        writeln!(io.stdout, "{name}")?;
+                    }
+                }
             }
 
-            Ok(())
+            Ok(ExitStatus::SUCCESS)
+        }
+
+        fn complete(&self, g: &Game, args: &[String], word: &str) -> Option<Vec<String>> {
+            if !args.is_empty() {
+                return Some(Vec::new());
+            }
+            Some(g.current_computer().complete_exe(word))
         }
     }
     &Help
@@ -0,0 +1,66 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgError, ArgSpec, Opt, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "history",
+    title: None,
+    description: "lists previously run commands.",
+    about: concat!(
+        "Lists the last n commands, or all of them, kept in ~/.history.\n",
+        "Rerun one with !n, or the last one with !!."
+    ),
+    opts: &[Opt::flag('c', "clear", "clears the history")],
+    args: &[Positional::optional("n")],
+};
+
+pub const HISTORY: Subprocess = {
+    struct History;
+
+    impl SubprocessFn for History {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let computer = g.current_computer();
+            let count = match (args.flag("clear"), args.positionals.first()) {
+                (true, Some(n)) => {
+                    return ARGS.usage_error(ArgError::UnexpectedArgument(n.clone()), io)
+                }
+                (true, None) => {
+                    if let Err(e) = computer.clear_history() {
+                        writeln!(io.stderr, "history: {e}")?;
+                        return Ok(ExitStatus::FAILURE);
+                    }
+                    return Ok(ExitStatus::SUCCESS);
+                }
+                (false, None) => None,
+                (false, Some(n)) => match n.parse::<usize>() {
+                    Ok(n) => Some(n),
+                    Err(_) => {
+                        let e = ArgError::InvalidValue("n".into(), n.clone());
+                        return ARGS.usage_error(e, io);
+                    }
+                },
+            };
+
+            let history = computer.history();
+            let skip = count.map_or(0, |n| history.len().saturating_sub(n));
+            for (i, line) in history.iter().enumerate().skip(skip) {
+                writeln!(io.stdout, "{:>5}  {line}", i + 1)?;
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &History
+};
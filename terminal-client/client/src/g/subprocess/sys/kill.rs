@@ -0,0 +1,60 @@
+use crate::g::{
+    process::Pid,
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "kill",
+    title: None,
+    description: "stops processes.",
+    about: concat!(
+        "Stops the processes with the given PIDs, as listed by ps.\n",
+        "Only root may stop the processes of other users.\n",
+        "A signal may come first; every one (-9, -KILL, -TERM, ...) stops the process."
+    ),
+    opts: &[],
+    args: &[Positional::one_or_more("pid")],
+};
+
+pub const KILL: Subprocess = {
+    struct Kill;
+
+    impl SubprocessFn for Kill {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            // there is nothing to catch signals, so they all kill
+            let args = match args.first() {
+                Some(signal) if signal.starts_with('-') && signal != "--" => &args[1..],
+                _ => &args[..],
+            };
+            let pids = match ARGS.parse(args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+
+            let mut status = ExitStatus::SUCCESS;
+            for arg in pids {
+                let Ok(pid) = arg.parse::<Pid>() else {
+                    writeln!(io.stderr, "kill: \"{arg}\": arguments must be process IDs")?;
+                    status = ExitStatus::USAGE;
+                    continue;
+                };
+
+                if let Err(e) = g.kill(pid) {
+                    writeln!(io.stderr, "kill: ({pid}) - {e}")?;
+                    status = ExitStatus::FAILURE;
+                }
+            }
+            Ok(status)
+        }
+    }
+
+    &Kill
+};
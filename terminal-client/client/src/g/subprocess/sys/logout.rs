@@ -1,5 +1,5 @@
 use crate::g::{
-    subprocess::{Subprocess, SubprocessFn},
+    subprocess::{ExitStatus, Stdio, Subprocess, SubprocessFn},
     Game,
 };
 
@@ -7,9 +7,14 @@ pub const LOGOUT: Subprocess = {
     struct Logout;
 
     impl SubprocessFn for Logout {
-        fn run(&self, game: &Game, _args: Vec<String>) -> std::io::Result<()> {
+        fn run(
+            &self,
+            game: &Game,
+            _args: Vec<String>,
+            _io: &mut Stdio,
+        ) -> std::io::Result<ExitStatus> {
             game.current_computer().should_quit.set(true);
-            Ok(())
+            Ok(ExitStatus::SUCCESS)
         }
     }
 
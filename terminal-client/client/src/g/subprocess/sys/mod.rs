@@ -2,23 +2,68 @@ use super::Subprocess;
 
 mod clear;
 mod cmd;
+mod crontab;
+mod date;
+mod echo;
+mod env;
+mod export;
 mod help;
+mod history;
+mod kill;
 mod logout;
+mod ps;
 mod ssh;
+mod su;
+mod sudo;
+mod test;
+mod top;
+mod unset;
 mod which;
+mod whoami;
 
 pub use clear::*;
 pub use cmd::*;
+pub use crontab::*;
+pub use date::*;
+pub use echo::*;
+pub use env::*;
+pub use export::*;
 pub use help::*;
+pub use history::*;
+pub use kill::*;
 pub use logout::*;
+pub use ps::*;
 pub use ssh::*;
+pub use su::*;
+pub use sudo::*;
+pub use test::*;
+pub use top::*;
+pub use unset::*;
 pub use which::*;
+pub use whoami::*;
 
 pub const DEFAULT: &[(&str, Subprocess)] = &[
     ("cmd", CMD),
     ("logout", LOGOUT),
     ("ssh", SSH),
+    ("su", SU),
+    ("sudo", SUDO),
     ("help", HELP),
+    ("history", HISTORY),
+    ("ps", PS),
+    ("kill", KILL),
+    ("top", TOP),
+    ("crontab", CRONTAB),
     ("which", WHICH),
+    ("whoami", WHOAMI),
     ("clear", CLEAR),
+    ("date", DATE),
+    ("echo", ECHO),
+    ("env", ENV),
+    ("export", EXPORT),
+    ("unset", UNSET),
+    ("test", TEST),
+    ("[", TEST),
+    ("true", TRUE),
+    ("false", FALSE),
 ];
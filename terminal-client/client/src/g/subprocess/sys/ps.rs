@@ -0,0 +1,60 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Opt},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "ps",
+    title: Some("Process Status"),
+    description: "lists running processes.",
+    about: "Lists every process, or only those of user.",
+    opts: &[Opt::flag('u', "user", "lists only the processes of user").with_value("user")],
+    args: &[],
+};
+
+pub const PS: Subprocess = {
+    struct Ps;
+
+    impl SubprocessFn for Ps {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let user = args.value("user");
+
+            let computer = g.current_computer();
+            let today = g.clock.now().date_naive();
+            writeln!(
+                io.stdout,
+                "{:>5} {:<8} {:<6} COMMAND",
+                "PID", "USER", "START"
+            )?;
+            for process in computer.processes.borrow().iter() {
+                if user.is_some_and(|user| *user != process.owner) {
+                    continue;
+                }
+                let start = if process.started.date_naive() == today {
+                    process.started.format("%H:%M")
+                } else {
+                    process.started.format("%b%d")
+                };
+                writeln!(
+                    io.stdout,
+                    "{:>5} {:<8} {:<6} {}",
+                    process.pid, process.owner, start, process.cmdline
+                )?;
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Ps
+};
@@ -1,56 +1,47 @@
-use rustyline::{config::Configurer, history::DefaultHistory};
-
 use crate::{
     g::{
-        subprocess::{Subprocess, SubprocessFn, SubprocessInfo},
+        subprocess::{
+            args::{ArgSpec, Positional},
+            ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+        },
         Game,
     },
     ipc::SwitchComputerMessage,
-    rl::password::PasswordHelper,
+    rl::password,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "ssh",
+    title: Some("Secure Shell"),
+    description: "creates a remote shell to another computer.",
+    about: "Asks for a username and password on host, then moves the session there.",
+    opts: &[],
+    args: &[Positional::one("host")],
 };
 
 pub const SSH: Subprocess = {
     struct Ssh;
     impl SubprocessFn for Ssh {
         fn info(&self) -> SubprocessInfo {
-            SubprocessInfo {
-                name: Some("Secure Shell".into()),
-                description: Some("creates a remote shell to another computer.".into()),
-                help_text: Some(concat!("ssh host\n").into()),
-            }
+            ARGS.info()
         }
 
-        fn run(&self, g: &Game, args: Vec<String>) -> std::io::Result<()> {
-            if args.len() != 1 {
-                let _ = g.start_exe_from_path("help", vec!["ssh".into()]);
-                return Ok(());
-            }
-
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
             let host = &args[0];
 
             if let Some(computer) = g.find_computer_by_address(host) {
-                let mut rl = rustyline::Editor::<PasswordHelper, DefaultHistory>::new()
-                    .map_err(std::io::Error::other)?;
-                rl.set_auto_add_history(false);
-                rl.set_color_mode(rustyline::ColorMode::Forced);
-
-                rl.set_helper(Some(PasswordHelper(false)));
-                let username = rl.readline("Username: ").unwrap_or(String::new());
+                let username = password::prompt("Username: ", false)?;
+                let password = password::prompt("Password: ", true)?;
 
-                let mut guard = rl
-                    .set_cursor_visibility(false)
-                    .map_err(std::io::Error::other)?;
-
-                rl.set_helper(Some(PasswordHelper(true)));
-                let password = rl.readline("Password: ").unwrap_or(String::new());
-
-                guard.take();
-
-                match computer.find_user(&username) {
-                    None => println!("Incorrect username or password"),
-                    Some(user) => {
-                        if password != user.password {
-                            println!("Incorrect password.");
+                match computer.find_user_index(&username) {
+                    None => writeln!(io.stderr, "Incorrect username or password")?,
+                    Some(user_index) => {
+                        if !computer.users[user_index].check_password(&password) {
+                            writeln!(io.stderr, "Incorrect password.")?;
                         } else {
                             g.connection.borrow_mut().write_message(
                                 crate::ipc::Message::SwitchComputer(SwitchComputerMessage {
@@ -59,18 +50,33 @@ pub const SSH: Subprocess = {
                             )?;
 
                             g.current_computer().should_quit.set(true);
-                            println!("Successfully connected");
+                            computer.current_user_index.set(user_index);
+                            computer.cwd.replace(computer.home());
+                            writeln!(io.stdout, "Successfully connected")?;
                             g.change_computers_by_address(host);
                             g.queue_process("cmd", []);
+                            return Ok(ExitStatus::SUCCESS);
                         }
                     }
                 }
             } else {
-                println!("Host does not exist.");
-                return Ok(());
+                writeln!(io.stderr, "Host does not exist.")?;
             }
 
-            Ok(())
+            Ok(ExitStatus::FAILURE)
+        }
+
+        fn complete(&self, g: &Game, args: &[String], word: &str) -> Option<Vec<String>> {
+            if !args.is_empty() {
+                return Some(Vec::new());
+            }
+            Some(
+                g.computers
+                    .iter()
+                    .map(|computer| computer.address.clone())
+                    .filter(|address| address.starts_with(word))
+                    .collect(),
+            )
         }
     }
     &Ssh
@@ -0,0 +1,67 @@
+use crate::{
+    g::{
+        subprocess::{
+            args::{ArgSpec, Positional},
+            ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+        },
+        Game,
+    },
+    rl::password,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "su",
+    title: Some("Substitute User"),
+    description: "starts a shell as another user.",
+    about: concat!(
+        "Starts a shell as user, or root if not specified.\n",
+        "Type \"exit\" to return to the previous user."
+    ),
+    opts: &[],
+    args: &[Positional::optional("user")],
+};
+
+pub const SU: Subprocess = {
+    struct Su;
+    impl SubprocessFn for Su {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+
+            let computer = g.current_computer();
+            let name = args.first().map(String::as_str).unwrap_or("root");
+            let Some(user_index) = computer.find_user_index(name) else {
+                writeln!(io.stderr, "User \"{name}\" does not exist.")?;
+                return Ok(ExitStatus::FAILURE);
+            };
+
+            if !computer.current_user().is_root() {
+                let password = password::prompt("Password: ", true)?;
+                if !computer.users[user_index].check_password(&password) {
+                    writeln!(io.stderr, "Authentication failure.")?;
+                    return Ok(ExitStatus::FAILURE);
+                }
+            }
+
+            let index = g.current_computer_index();
+            let previous_user_index = computer.current_user_index.replace(user_index);
+            let res = g.start_exe_from_path("cmd", [], io);
+            // after `ssh`, the session has left for another computer
+            if g.current_computer_index() == index {
+                computer.current_user_index.set(previous_user_index);
+            }
+
+            res.unwrap_or_else(|e| {
+                writeln!(io.stderr, "Could not start shell: {e}")?;
+                Ok(ExitStatus::NOT_EXECUTABLE)
+            })
+        }
+    }
+    &Su
+};
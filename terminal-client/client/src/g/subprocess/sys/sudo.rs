@@ -0,0 +1,97 @@
+use crate::{
+    g::{
+        fs::FsError,
+        subprocess::{
+            args::{ArgError, ArgSpec, Opt, Positional},
+            ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+        },
+        Game,
+    },
+    rl::password,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "sudo",
+    title: Some("Superuser Do"),
+    description: "runs an executable as another user.",
+    about: concat!(
+        "Runs exe_name as user, or root if not specified.\n",
+        "Only users listed in /etc/sudoers may use sudo."
+    ),
+    opts: &[Opt::flag('u', "user", "runs as user instead of root").with_value("user")],
+    args: &[Positional::one("exe_name"), Positional::many("args")],
+};
+
+pub const SUDO: Subprocess = {
+    struct Sudo;
+    impl SubprocessFn for Sudo {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            // not `ARGS.parse`, which would take the options of exe_name too
+            let (name, args) = match &args[..] {
+                [flag, user, rest @ ..] if flag == "-u" => (user.as_str(), rest),
+                [flag] if flag == "-u" => {
+                    return ARGS.usage_error(ArgError::MissingValue(flag.clone()), io)
+                }
+                [flag, ..] if flag.starts_with('-') => {
+                    return ARGS.usage_error(ArgError::UnknownOption(flag.clone()), io)
+                }
+                rest => ("root", rest),
+            };
+            if args.is_empty() {
+                return ARGS.usage_error(ArgError::MissingArgument("exe_name"), io);
+            }
+
+            let computer = g.current_computer();
+            let current_user = computer.current_user();
+            if !computer.can_sudo(current_user) {
+                writeln!(
+                    io.stderr,
+                    "{} is not in the sudoers file. This incident will be reported.",
+                    current_user.name
+                )?;
+                return Ok(ExitStatus::FAILURE);
+            }
+
+            let Some(user_index) = computer.find_user_index(name) else {
+                writeln!(io.stderr, "User \"{name}\" does not exist.")?;
+                return Ok(ExitStatus::FAILURE);
+            };
+
+            if !current_user.is_root() {
+                let password = password::prompt(
+                    &format!("[sudo] password for {}: ", current_user.name),
+                    true,
+                )?;
+                if !current_user.check_password(&password) {
+                    writeln!(io.stderr, "Sorry, try again.")?;
+                    return Ok(ExitStatus::FAILURE);
+                }
+            }
+
+            let index = g.current_computer_index();
+            let previous_user_index = computer.current_user_index.replace(user_index);
+            let res = g.start_exe_from_path(&args[0], &args[1..], io);
+            // after `ssh`, the session has left for another computer
+            if g.current_computer_index() == index {
+                computer.current_user_index.set(previous_user_index);
+            }
+
+            match res {
+                Ok(res) => res,
+                Err(FsError::NotExecutable) => {
+                    writeln!(io.stderr, "sudo: {}: command not found", args[0])?;
+                    Ok(ExitStatus::NOT_FOUND)
+                }
+                Err(e) => {
+                    writeln!(io.stderr, "sudo: {}: {e}", args[0])?;
+                    Ok(ExitStatus::NOT_EXECUTABLE)
+                }
+            }
+        }
+    }
+    &Sudo
+};
@@ -0,0 +1,140 @@
+use crate::g::{
+    fs::SecurityMode,
+    subprocess::{ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo},
+    Game,
+};
+
+/// Evaluates a `test` expression. Returns `None` if it is malformed.
+fn evaluate(g: &Game, args: &[&str]) -> Option<bool> {
+    let computer = g.current_computer();
+    match args {
+        [] => Some(false),
+        ["!", rest @ ..] => evaluate(g, rest).map(|b| !b),
+        [s] => Some(!s.is_empty()),
+        ["-n", s] => Some(!s.is_empty()),
+        ["-z", s] => Some(s.is_empty()),
+        [flag @ ("-e" | "-f" | "-d" | "-r" | "-w" | "-x"), path] => {
+            let Ok(node) = computer.lookup(&computer.parse_path(path)) else {
+                return Some(false);
+            };
+            let permits = |mode| computer.check_access(&node, mode).is_ok();
+            Some(match *flag {
+                "-f" => node.is_file(),
+                "-d" => node.is_dir(),
+                "-r" => permits(SecurityMode::Read),
+                "-w" => permits(SecurityMode::Write),
+                "-x" => permits(SecurityMode::Execute),
+                _ => true,
+            })
+        }
+        [a, "=" | "==", b] => Some(a == b),
+        [a, "!=", b] => Some(a != b),
+        [a, op @ ("-eq" | "-ne" | "-lt" | "-le" | "-gt" | "-ge"), b] => {
+            let (a, b) = (a.parse::<i64>().ok()?, b.parse::<i64>().ok()?);
+            Some(match *op {
+                "-eq" => a == b,
+                "-ne" => a != b,
+                "-lt" => a < b,
+                "-le" => a <= b,
+                "-gt" => a > b,
+                _ => a >= b,
+            })
+        }
+        _ => None,
+    }
+}
+
+pub const TEST: Subprocess = {
+    struct Test;
+
+    impl SubprocessFn for Test {
+        fn info(&self) -> SubprocessInfo {
+            SubprocessInfo {
+                name: None,
+                description: Some("checks a condition, for use with if.".into()),
+                help_text: Some(
+                    concat!(
+                        "test expression\n",
+                        "[ expression ]\n",
+                        "\tExits with 0 if expression is true, and 1 otherwise.\n",
+                        "\t-e/-f/-d path: path exists/is a file/is a directory\n",
+                        "\t-r/-w/-x path: path is readable/writable/executable\n",
+                        "\t-n/-z string: string is non-empty/empty\n",
+                        "\ta = b, a != b: string comparison\n",
+                        "\ta -eq b, -ne, -lt, -le, -gt, -ge: number comparison\n",
+                        "\t! expression: negation"
+                    )
+                    .into(),
+                ),
+            }
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            // `[` is the same program, but expects a closing `]`
+            let args = match args.split_last() {
+                Some((last, rest)) if last == "]" => rest,
+                _ => &args[..],
+            };
+
+            match evaluate(g, &args.iter().map(String::as_str).collect::<Vec<_>>()) {
+                Some(result) => Ok(result.into()),
+                None => {
+                    writeln!(io.stderr, "test: invalid expression")?;
+                    Ok(ExitStatus::USAGE)
+                }
+            }
+        }
+    }
+
+    &Test
+};
+
+pub const TRUE: Subprocess = {
+    struct True;
+
+    impl SubprocessFn for True {
+        fn info(&self) -> SubprocessInfo {
+            SubprocessInfo {
+                name: None,
+                description: Some("does nothing, successfully.".into()),
+                help_text: Some("true".into()),
+            }
+        }
+
+        fn run(
+            &self,
+            _g: &Game,
+            _args: Vec<String>,
+            _io: &mut Stdio,
+        ) -> std::io::Result<ExitStatus> {
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &True
+};
+
+pub const FALSE: Subprocess = {
+    struct False;
+
+    impl SubprocessFn for False {
+        fn info(&self) -> SubprocessInfo {
+            SubprocessInfo {
+                name: None,
+                description: Some("does nothing, unsuccessfully.".into()),
+                help_text: Some("false".into()),
+            }
+        }
+
+        fn run(
+            &self,
+            _g: &Game,
+            _args: Vec<String>,
+            _io: &mut Stdio,
+        ) -> std::io::Result<ExitStatus> {
+            Ok(ExitStatus::FAILURE)
+        }
+    }
+
+    &False
+};
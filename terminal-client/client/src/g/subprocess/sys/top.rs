@@ -0,0 +1,316 @@
+use std::time::{Duration, Instant};
+
+use crossterm::{
+    event::{self, KeyCode, KeyEventKind},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use ratatui::{
+    layout::{Constraint, Layout},
+    prelude::CrosstermBackend,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+use crate::g::{
+    computer::Computer,
+    process::{Pid, Process},
+    subprocess::{
+        args::ArgSpec, is_interrupt_key, ExitStatus, Stdio, Subprocess, SubprocessFn,
+        SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "top",
+    title: None,
+    description: "monitors processes and resource use.",
+    about: concat!(
+        "Shows the processes of this computer and what they use, updated live.\n",
+        "P, M and N sort by CPU, memory and PID; the arrows select a process\n",
+        "and k kills it, as kill would. q quits."
+    ),
+    opts: &[],
+    args: &[],
+};
+
+/// How often the figures change.
+const REFRESH: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortBy {
+    Cpu,
+    Memory,
+    Pid,
+}
+
+struct TopState {
+    sort_by: SortBy,
+    /// Followed across refreshes, as rows move when sorted.
+    selected: Option<Pid>,
+    /// Set after `k`, until the kill is confirmed with `y`.
+    confirm_kill: Option<Pid>,
+    message: Option<String>,
+    tick: u64,
+}
+
+/// A process as shown in one frame.
+struct Sample {
+    pid: Pid,
+    owner: String,
+    cpu: f32,
+    memory: u64,
+    cmdline: String,
+}
+
+impl Sample {
+    fn new(process: &Process, tick: u64) -> Self {
+        Self {
+            pid: process.pid,
+            owner: process.owner.clone(),
+            cpu: process.cpu_at(tick),
+            memory: process.memory,
+            cmdline: process.cmdline.clone(),
+        }
+    }
+}
+
+fn samples(computer: &Computer, state: &TopState) -> Vec<Sample> {
+    let mut samples = computer
+        .processes
+        .borrow()
+        .iter()
+        .map(|p| Sample::new(p, state.tick))
+        .collect::<Vec<_>>();
+    match state.sort_by {
+        SortBy::Cpu => samples.sort_by(|a, b| b.cpu.total_cmp(&a.cpu).then(a.pid.cmp(&b.pid))),
+        SortBy::Memory => samples.sort_by(|a, b| b.memory.cmp(&a.memory).then(a.pid.cmp(&b.pid))),
+        SortBy::Pid => samples.sort_by_key(|s| s.pid),
+    }
+    samples
+}
+
+/// Formats how long ago `since` was, as `top` does: `3 days, 4:05`.
+fn uptime(since: chrono::Duration) -> String {
+    let (days, hours, minutes) = (
+        since.num_days(),
+        since.num_hours() % 24,
+        since.num_minutes() % 60,
+    );
+    match days {
+        0 => format!("{hours}:{minutes:02}"),
+        1 => format!("1 day, {hours}:{minutes:02}"),
+        days => format!("{days} days, {hours}:{minutes:02}"),
+    }
+}
+
+fn draw(frame: &mut Frame, computer: &Computer, state: &TopState, samples: &[Sample]) {
+    let layout = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .split(frame.size());
+
+    let now = computer.clock.now();
+    let booted = computer
+        .processes
+        .borrow()
+        .get(1)
+        .map_or_else(|| computer.root.node.borrow().date, |init| init.started);
+    let cpu = samples.iter().map(|s| s.cpu).sum::<f32>().min(100.0);
+    let used = samples
+        .iter()
+        .map(|s| s.memory)
+        .sum::<u64>()
+        .min(computer.memory);
+    let mib = |kib: u64| kib as f64 / 1024.0;
+    let users = samples
+        .iter()
+        .map(|s| &s.owner)
+        .collect::<std::collections::BTreeSet<_>>()
+        .len();
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::raw(format!(
+                "top - {} {} up {}, {users} user(s)",
+                computer.name,
+                now.format("%H:%M:%S"),
+                uptime(now - booted),
+            )),
+            Line::raw(format!("Tasks: {:>4} total", samples.len())),
+            Line::raw(format!("%Cpu(s): {cpu:>5.1} us, {:>5.1} id", 100.0 - cpu)),
+            Line::raw(format!(
+                "MiB Mem: {:>8.1} total, {:>8.1} free, {:>8.1} used",
+                mib(computer.memory),
+                mib(computer.memory - used),
+                mib(used),
+            )),
+        ]),
+        layout[0],
+    );
+
+    let rows = samples.iter().map(|s| {
+        let row = Row::new([
+            format!("{:>5}", s.pid),
+            s.owner.clone(),
+            format!("{:>5.1}", s.cpu),
+            format!("{:>5.1}", s.memory as f64 * 100.0 / computer.memory as f64),
+            format!("{:>8}", s.memory),
+            s.cmdline.clone(),
+        ]);
+        // make whatever hogs the CPU hard to miss
+        if s.cpu >= 50.0 {
+            row.style(Style::new().fg(Color::Red))
+        } else {
+            row
+        }
+    });
+    let header = Row::new([
+        format!("{:>5}", "PID"),
+        "USER".into(),
+        format!("{:>5}", "%CPU"),
+        format!("{:>5}", "%MEM"),
+        format!("{:>8}", "RES"),
+        "COMMAND".into(),
+    ])
+    .style(Style::new().fg(Color::Black).bg(Color::White));
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(header)
+    .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut table_state = TableState::default().with_selected(
+        state
+            .selected
+            .and_then(|pid| samples.iter().position(|s| s.pid == pid)),
+    );
+    frame.render_stateful_widget(table, layout[1], &mut table_state);
+
+    let status = state.message.clone().unwrap_or_else(|| {
+        "q quit  P/M/N sort by cpu/memory/pid  \u{2191}\u{2193} select  k kill".into()
+    });
+    frame.render_widget(
+        Paragraph::new(status).style(Style::new().add_modifier(Modifier::BOLD)),
+        layout[2],
+    );
+}
+
+/// Handles a key press; returns whether to quit.
+fn handle_key(g: &Game, state: &mut TopState, samples: &[Sample], code: KeyCode) -> bool {
+    if let Some(pid) = state.confirm_kill.take() {
+        state.message = match code {
+            KeyCode::Char('y') => Some(match g.kill(pid) {
+                Ok(()) => format!("Killed {pid}"),
+                Err(e) => format!("kill: ({pid}) - {e}"),
+            }),
+            _ => None,
+        };
+        return false;
+    }
+    state.message = None;
+
+    let index = state
+        .selected
+        .and_then(|pid| samples.iter().position(|s| s.pid == pid));
+    match code {
+        KeyCode::Char('q') | KeyCode::Esc => return true,
+        KeyCode::Char('P') => state.sort_by = SortBy::Cpu,
+        KeyCode::Char('M') => state.sort_by = SortBy::Memory,
+        KeyCode::Char('N') => state.sort_by = SortBy::Pid,
+        KeyCode::Up => {
+            let index = index.map_or(0, |i| i.saturating_sub(1));
+            state.selected = samples.get(index).map(|s| s.pid);
+        }
+        KeyCode::Down => {
+            let index = index.map_or(0, |i| (i + 1).min(samples.len().saturating_sub(1)));
+            state.selected = samples.get(index).map(|s| s.pid);
+        }
+        KeyCode::Char('k') => match index.map(|i| &samples[i]) {
+            Some(sample) => {
+                state.confirm_kill = Some(sample.pid);
+                state.message = Some(format!(
+                    "Kill PID {} ({})? [y/N]",
+                    sample.pid, sample.cmdline
+                ));
+            }
+            None => state.message = Some("Select a process to kill first".into()),
+        },
+        _ => {}
+    }
+    false
+}
+
+pub const TOP: Subprocess = {
+    struct Top;
+
+    impl SubprocessFn for Top {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            if let Err(e) = ARGS.parse(&args) {
+                return ARGS.usage_error(e, io);
+            }
+
+            let computer = g.current_computer();
+            let mut state = TopState {
+                sort_by: SortBy::Cpu,
+                selected: None,
+                confirm_kill: None,
+                message: None,
+                tick: 0,
+            };
+
+            std::io::stdout().execute(EnterAlternateScreen)?;
+            terminal::enable_raw_mode()?;
+            let mut term = ratatui::Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+
+            let started = Instant::now();
+            while !g.interrupted() {
+                state.tick = (started.elapsed().as_millis() / REFRESH.as_millis()) as u64;
+                let samples = samples(&computer, &state);
+                term.draw(|frame| draw(frame, &computer, &state, &samples))?;
+
+                if !event::poll(Duration::from_millis(100))? {
+                    g.run_cron();
+                    continue;
+                }
+                if let event::Event::Key(k) = event::read()? {
+                    if k.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    if is_interrupt_key(&k) {
+                        g.interrupt();
+                        break;
+                    }
+                    if handle_key(g, &mut state, &samples, k.code) {
+                        break;
+                    }
+                }
+            }
+
+            terminal::disable_raw_mode()?;
+            std::io::stdout().execute(LeaveAlternateScreen)?;
+
+            if g.interrupted() {
+                return Ok(ExitStatus::INTERRUPTED);
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Top
+};
@@ -0,0 +1,42 @@
+use crate::g::{
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
+    Game,
+};
+
+const ARGS: ArgSpec = ArgSpec {
+    name: "unset",
+    title: None,
+    description: "removes environment variables.",
+    about: "Removes each named variable from the environment.",
+    opts: &[],
+    args: &[Positional::one_or_more("name")],
+};
+
+pub const UNSET: Subprocess = {
+    struct Unset;
+
+    impl SubprocessFn for Unset {
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args.positionals,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+
+            let computer = g.current_computer();
+            let mut env = computer.env.borrow_mut();
+            for name in args {
+                env.remove(&name);
+            }
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Unset
+};
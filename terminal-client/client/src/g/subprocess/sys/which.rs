@@ -1,22 +1,41 @@
 use crate::g::{
-    subprocess::{Subprocess, SubprocessFn},
+    subprocess::{
+        args::{ArgSpec, Positional},
+        ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo,
+    },
     Game,
 };
 
+const ARGS: ArgSpec = ArgSpec {
+    name: "which",
+    title: None,
+    description: "shows where an executable is.",
+    about: "Prints the path of the executable that runs as exe_name.",
+    opts: &[],
+    args: &[Positional::one("exe_name")],
+};
+
 pub const WHICH: Subprocess = {
     struct Which;
 
     impl SubprocessFn for Which {
-        fn run(&self, g: &Game, args: Vec<String>) -> std::io::Result<()> {
-            if args.len() != 1 {
-                return Ok(());
-            }
-            if let Some(path) = g.current_computer().which_path(&args[0]) {
-                println!("{path}");
+        fn info(&self) -> SubprocessInfo {
+            ARGS.info()
+        }
+
+        fn run(&self, g: &Game, args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            let args = match ARGS.parse(&args) {
+                Ok(args) => args,
+                Err(e) => return ARGS.usage_error(e, io),
+            };
+            let name = &args.positionals[0];
+            if let Some(path) = g.current_computer().which_path(name) {
+                writeln!(io.stdout, "{path}")?;
+                Ok(ExitStatus::SUCCESS)
             } else {
-                println!("Could not find executable: \"{}\"", args[0])
+                writeln!(io.stderr, "Could not find executable: \"{name}\"")?;
+                Ok(ExitStatus::FAILURE)
             }
-            Ok(())
         }
     }
 
@@ -0,0 +1,25 @@
+use crate::g::{
+    subprocess::{ExitStatus, Stdio, Subprocess, SubprocessFn, SubprocessInfo},
+    Game,
+};
+
+pub const WHOAMI: Subprocess = {
+    struct Whoami;
+
+    impl SubprocessFn for Whoami {
+        fn info(&self) -> SubprocessInfo {
+            SubprocessInfo {
+                name: None,
+                description: Some("prints the name of the current user.".into()),
+                help_text: Some("whoami".into()),
+            }
+        }
+
+        fn run(&self, g: &Game, _args: Vec<String>, io: &mut Stdio) -> std::io::Result<ExitStatus> {
+            writeln!(io.stdout, "{}", g.current_computer().current_user().name)?;
+            Ok(ExitStatus::SUCCESS)
+        }
+    }
+
+    &Whoami
+};
@@ -88,6 +88,10 @@ impl Connection for StreamConnection {
             Message::UnlockDoor(data) => self.write_message_internal(msg.get_type(), &data),
             Message::SwitchComputer(data) => self.write_message_internal(msg.get_type(), &data),
             Message::PlaySfx(data) => self.write_message_internal(msg.get_type(), &data),
+            Message::SetClock(data) => self.write_message_internal(msg.get_type(), &data),
+            Message::SaveState(ref state) | Message::LoadState(ref state) => {
+                self.write_blob_message_internal(msg.get_type(), &state.data)
+            }
         }
     }
 
@@ -156,11 +160,24 @@ impl StreamConnection {
         .and_then(|_| self.write(data))
     }
 
+    fn write_blob_message_internal(&mut self, ty: MessageType, data: &[u8]) -> std::io::Result<()> {
+        self.write(&MessageHeader {
+            ty,
+            len: data.len() as u32,
+        })
+        .and_then(|_| self.stream.write_all(data))
+    }
+
     fn parse_message(&mut self, header: &MessageHeader) -> Result<Message, ParseError> {
         match header.ty {
+            MessageType::LoadState => self
+                .read_exact(header.len as usize)
+                .map(|data| Message::LoadState(StateMessage { data }))
+                .map_err(ParseError::Io),
             MessageType::Initialize => self.parse_message_type::<InitializeMessage>(header),
             MessageType::InitializeOS => self.parse_message_type::<InitializeOSMessage>(header),
             MessageType::UnlockDoor => self.parse_message_type::<UnlockDoorMessage>(header),
+            MessageType::SetClock => self.parse_message_type::<SetClockMessage>(header),
             #[allow(unreachable_patterns)]
             // the server can send messages that cannot be casted to MessageType
             _ => Err(ParseError::UnknownMessage),
@@ -171,7 +188,7 @@ impl StreamConnection {
         &mut self,
         header: &MessageHeader,
     ) -> Result<Message, ParseError> {
-        if header.len as usize != core::mem::size_of::<InitializeMessage>() {
+        if header.len as usize != core::mem::size_of::<T>() {
             Err(ParseError::InvalidLength)
         } else {
             self.read_value::<T>()
@@ -15,9 +15,12 @@ pub enum MessageType {
     UnlockDoor = 1,
     SwitchComputer = 2,
     PlaySfx = 3,
+    SaveState = 5,
+    LoadState = 6,
+    SetClock = 7,
 }
 
-#[derive(Reflect, Debug, Clone, Copy)]
+#[derive(Reflect, Debug, Clone)]
 #[type_path = "c"]
 pub enum Message {
     Initialize(InitializeMessage),
@@ -25,6 +28,14 @@ pub enum Message {
     UnlockDoor(UnlockDoorMessage),
     SwitchComputer(SwitchComputerMessage),
     PlaySfx(PlaySfxMessage),
+    /// Sent to Unity with the game state to store in its save slot.
+    SaveState(StateMessage),
+    /// Sent by Unity before `InitializeOS` to restore a game state.
+    LoadState(StateMessage),
+    /// Sent by Unity to set the game clock. Only read before `InitializeOS`:
+    /// the client reads no messages once the game runs, so the clock cannot
+    /// be changed later.
+    SetClock(SetClockMessage),
 }
 
 impl Message {
@@ -35,6 +46,9 @@ impl Message {
             Self::UnlockDoor(_) => MessageType::UnlockDoor,
             Self::SwitchComputer(_) => MessageType::SwitchComputer,
             Self::PlaySfx(_) => MessageType::PlaySfx,
+            Self::SaveState(_) => MessageType::SaveState,
+            Self::LoadState(_) => MessageType::LoadState,
+            Self::SetClock(_) => MessageType::SetClock,
         }
     }
 }
@@ -74,6 +88,28 @@ pub struct PlaySfxMessage {
     pub id: u32,
 }
 
+#[derive(Reflect, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SetClockMessage {
+    /// The game time, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// Game seconds per real second. 0 freezes the clock.
+    pub scale: f64,
+}
+
+/// An opaque, variably sized game state blob.
+/// Unlike other messages, the body is the raw data rather than the struct itself.
+#[derive(Reflect, Clone)]
+pub struct StateMessage {
+    pub data: Vec<u8>,
+}
+
+impl std::fmt::Debug for StateMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StateMessage {{ {} bytes }}", self.data.len())
+    }
+}
+
 impl From<InitializeMessage> for Message {
     fn from(value: InitializeMessage) -> Self {
         Self::Initialize(value)
@@ -98,6 +134,12 @@ impl From<SwitchComputerMessage> for Message {
     }
 }
 
+impl From<SetClockMessage> for Message {
+    fn from(value: SetClockMessage) -> Self {
+        Self::SetClock(value)
+    }
+}
+
 impl From<PlaySfxMessage> for Message {
     fn from(value: PlaySfxMessage) -> Self {
         Self::PlaySfx(value)
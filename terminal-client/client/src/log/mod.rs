@@ -5,7 +5,7 @@ static mut LOG_PATH: Option<PathBuf> = None;
 
 pub fn init() {
     unsafe {
-        let log_dir_path = dirs::data_local_dir().map(|dir| dir.join("ketexon/termgame/logs"));
+        let log_dir_path = crate::data_dir().map(|dir| dir.join("logs"));
 
         LOG_PATH = log_dir_path.clone().map(|dir| {
             dir.join(format!(
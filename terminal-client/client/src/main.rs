@@ -46,6 +46,11 @@ fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
     area
 }
 
+/// Directory for logs and save files.
+pub fn data_dir() -> Option<std::path::PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("ketexon/termgame"))
+}
+
 fn setup() {
     log::init();
 }
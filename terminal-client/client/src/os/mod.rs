@@ -2,12 +2,24 @@ use std::{cell::RefCell, io::Result};
 
 use crate::g::computer::ComputerId;
 use crate::{
-    g::{self, fs::FsError},
+    g::{self, fs::FsError, subprocess::Stdio},
     ipc, log, GExitCode,
 };
 
 pub fn os_terminal(connection: Box<RefCell<dyn ipc::Connection>>) -> Result<GExitCode> {
-    let initial_computer = match connection.borrow_mut().read_message() {
+    let mut saved_state = None;
+    let mut clock = None;
+    let mut msg = connection.borrow_mut().read_message();
+    loop {
+        match msg {
+            Ok(ipc::Message::LoadState(state)) => saved_state = Some(state.data),
+            Ok(ipc::Message::SetClock(set_clock)) => clock = Some(set_clock),
+            _ => break,
+        }
+        msg = connection.borrow_mut().read_message();
+    }
+
+    let initial_computer = match msg {
         Ok(msg) => match msg {
             ipc::Message::InitializeOS(msg) => msg.computer_id,
             other => {
@@ -25,6 +37,23 @@ pub fn os_terminal(connection: Box<RefCell<dyn ipc::Connection>>) -> Result<GExi
 
     let g = g::Game::new(connection, initial_computer);
 
+    let loaded = match saved_state {
+        Some(data) => g.deserialize_state(&data),
+        None => g.load_from_disk(),
+    };
+    // Unity's clock wins over the saved one
+    if let Some(clock) = clock {
+        let time = chrono::DateTime::from_timestamp(clock.timestamp, 0).unwrap_or_default();
+        g.set_clock(time, clock.scale);
+    }
+    if loaded && g.current_computer().id != initial_computer {
+        g.connection
+            .borrow_mut()
+            .write_message(ipc::Message::SwitchComputer(ipc::SwitchComputerMessage {
+                new_id: g.current_computer().id,
+            }))?;
+    }
+
     // this is so that, for certain tiling window managers
     // with certain term emulators
     // (caugh caugh hyprland/urxvt), they clear after
@@ -33,17 +62,27 @@ pub fn os_terminal(connection: Box<RefCell<dyn ipc::Connection>>) -> Result<GExi
     g.queue_process("clear", []);
     g.queue_process("cmd", []);
     while let Some((name, args)) = g.get_queued_process() {
-        let res = g.start_exe_from_path(&name, args).map_err(|e| match e {
-            FsError::NotExecutable => {
-                std::io::Error::other("Tried to run process that does not exist")
-            }
-            e => std::io::Error::other(format!("Unknown error: {e:?}")),
-        });
+        let res =
+            Stdio::terminal(|io| g.start_exe_from_path(&name, args, io)).map_err(|e| match e {
+                FsError::NotExecutable => {
+                    std::io::Error::other("Tried to run process that does not exist")
+                }
+                e => std::io::Error::other(format!("Unknown error: {e:?}")),
+            });
 
         if let Err(e) = res {
             log!("Error in os_terminal: {e:?}");
         }
     }
 
+    if let Err(e) = g.save_to_disk() {
+        log!("Could not save game: {e:?}");
+    }
+    g.connection
+        .borrow_mut()
+        .write_message(ipc::Message::SaveState(ipc::StateMessage {
+            data: g.serialize_state(),
+        }))?;
+
     Ok(GExitCode::Success)
 }
@@ -17,3 +17,28 @@ impl Highlighter for PasswordHelper {
         self.0
     }
 }
+
+/// Reads a single line from the terminal.
+/// If `hidden`, the typed characters and cursor are not shown.
+pub fn prompt(prompt: &str, hidden: bool) -> std::io::Result<String> {
+    use rustyline::{config::Configurer, history::DefaultHistory};
+
+    let mut rl = rustyline::Editor::<PasswordHelper, DefaultHistory>::new()
+        .map_err(std::io::Error::other)?;
+    rl.set_auto_add_history(false);
+    rl.set_color_mode(rustyline::ColorMode::Forced);
+    rl.set_helper(Some(PasswordHelper(hidden)));
+
+    let mut guard = if hidden {
+        rl.set_cursor_visibility(false)
+            .map_err(std::io::Error::other)?
+    } else {
+        None
+    };
+
+    let line = rl.readline(prompt).unwrap_or_default();
+
+    guard.take();
+
+    Ok(line)
+}